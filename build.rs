@@ -56,8 +56,12 @@ fn generate_man_page<P: AsRef<Path>>(outdir: P) -> io::Result<()> {
         .replace("&#123;", "{")
         .replace("&#125;", "}");
     tpl = tpl.replace("{{OPTIONS}}", &options);
+    tpl = tpl.replace("{{ENVIRONMENT}}", &formatted_environment());
     tpl = tpl.replace("{{VERSION}}", clap::crate_version!());
-    tpl = tpl.replace("{{AUTHORS}}", clap::crate_authors!(",\n"));
+    tpl = tpl.replace(
+        "{{AUTHORS}}",
+        &env!("CARGO_PKG_AUTHORS").replace(':', ",\n"),
+    );
     File::create(&txt_path)?.write_all(tpl.as_bytes())?;
 
     let result = process::Command::new("asciidoctor")
@@ -92,6 +96,20 @@ fn formatted_options() -> io::Result<String> {
     Ok(formatted.join("\n\n"))
 }
 
+fn formatted_environment() -> String {
+    let mut formatted = vec![];
+    for arg in app::all_args_and_flags() {
+        if let (Some(env), false) = (arg.env, arg.hidden) {
+            formatted.push(format!(
+                "*{}*::\n  Supplies *{}*.",
+                env,
+                app::long_name(&arg.kind)
+            ));
+        }
+    }
+    formatted.join("\n\n")
+}
+
 fn formatted_arg(arg: &CustomArg) -> io::Result<String> {
     match arg.kind {
         CustomArgKind::Positional { .. } => {
@@ -167,5 +185,5 @@ fn formatted_doc_txt(arg: &CustomArg) -> io::Result<String> {
 }
 
 fn ioerr(msg: String) -> io::Error {
-    io::Error::new(io::ErrorKind::Other, msg)
+    io::Error::other(msg)
 }
@@ -24,6 +24,15 @@ Options
 -------
 {{OPTIONS}}
 
+ENVIRONMENT
+-----------
+Every option can also be supplied by an environment variable. These take precedence over the
+configuration file, but not over the command line, and are ignored for the options that conflict
+with the ones on the command line. A switch is enabled by setting its variable to *1*, *true*,
+*yes* or *on*, and disabled by setting it to *0*, *false*, *no* or *off*.
+
+{{ENVIRONMENT}}
+
 VERSION
 -------
 {{VERSION}}
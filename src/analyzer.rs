@@ -0,0 +1,428 @@
+//! This module is responsible for the sentiment analysis that is used to guess the color of
+//! the output. Any type implementing [`SentimentAnalyzer`](trait.SentimentAnalyzer.html) can
+//! be used by `printr`, the default being the [AFINN](https://github.com/fnielsen/afinn)
+//! based [`Afinn`](struct.Afinn.html).
+use crate::{emoji, heuristics, language::Language, lexicon::Lexicon};
+use std::{
+    collections::HashMap,
+    sync::{Mutex, OnceLock, PoisonError},
+};
+
+/// A type that can determine the sentiment of a piece of text.
+///
+/// # Example
+///
+/// ```
+/// use printr::analyzer::{tokenize, Sentiment, SentimentAnalyzer, Token};
+///
+/// /// Everything that mentions a failure is negative.
+/// struct FailureDetector;
+///
+/// impl SentimentAnalyzer for FailureDetector {
+///     fn analyze(&self, text: &str) -> Sentiment {
+///         let tokens: Vec<Token> = tokenize(text)
+///             .into_iter()
+///             .map(|word| {
+///                 let score = if word.eq_ignore_ascii_case("failed") { -3.0 } else { 0.0 };
+///                 Token::new(word, score)
+///             })
+///             .collect();
+///         Sentiment::from_tokens(tokens)
+///     }
+/// }
+///
+/// let sentiment = FailureDetector.analyze("The build failed");
+/// assert_eq!(sentiment.negative, 3.0);
+/// ```
+pub trait SentimentAnalyzer {
+    /// Analyse `text` and return its sentiment.
+    fn analyze(&self, text: &str) -> Sentiment;
+}
+
+/// The default analyzer, which scores English words using the AFINN lexicon and words of
+/// the other supported [`Language`](../language/enum.Language.html)s using the lexicons
+/// bundled with `printr`. The entries of a user supplied
+/// [`Lexicon`](../lexicon/struct.Lexicon.html) take precedence over the bundled ones.
+///
+/// The scores are then adjusted by the [`heuristics`](../heuristics/index.html), so that
+/// negations, intensifiers and the like are accounted for.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Afinn {
+    lexicon: Lexicon,
+    language: Language,
+}
+
+impl Afinn {
+    /// Create an analyzer which looks up words in `lexicon` before the AFINN lexicon.
+    pub fn with_lexicon(lexicon: Lexicon) -> Self {
+        Self {
+            lexicon,
+            ..Self::default()
+        }
+    }
+
+    /// Analyse text in `language` instead of English. If this is `Language::Auto`, the
+    /// language of every text is detected before it is analysed.
+    pub fn language(mut self, language: Language) -> Self {
+        self.language = language;
+        self
+    }
+}
+
+impl SentimentAnalyzer for Afinn {
+    fn analyze(&self, text: &str) -> Sentiment {
+        let language = self.language.resolve(text);
+        let bundled = language.lexicon();
+        let spans = token_spans(text);
+        let mut tokens = vec![];
+        let mut index = 0;
+        while index < spans.len() {
+            let (score, len) = lexicon_phrase(&self.lexicon, text, &spans[index..])
+                .or_else(|| lexicon_phrase(bundled?, text, &spans[index..]))
+                .unwrap_or_else(|| {
+                    let (start, end) = spans[index];
+                    let word = &text[start..end];
+                    let score = emoji::score(word)
+                        .or_else(|| bundled.is_none().then(|| afinn_score(word)))
+                        .unwrap_or(0.0);
+                    (score, 1)
+                });
+            let (start, end) = (spans[index].0, spans[index + len - 1].1);
+            tokens.push(Token::new(&text[start..end], score));
+            index += len;
+        }
+        heuristics::apply(text, &mut tokens, language);
+        Sentiment::from_tokens(tokens)
+    }
+}
+
+// the AFINN score of `word`, which is only looked up once per process since the `sentiment`
+// crate compiles its regexes again for every text it analyses
+fn afinn_score(word: &str) -> f32 {
+    static SCORES: OnceLock<Mutex<HashMap<String, f32>>> = OnceLock::new();
+    let word = word.to_lowercase();
+    // the crate splits the text at everything but letters, `0` and `-`, so no other word is
+    // in its lexicon
+    if !word
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c == '0' || c == '-')
+    {
+        return 0.0;
+    }
+    let mut scores = SCORES
+        .get_or_init(Mutex::default)
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    *scores
+        .entry(word)
+        .or_insert_with_key(|word| sentiment::analyze(word.clone()).score)
+}
+
+// find the longest phrase of `lexicon` that starts at the first of `spans`, returning its
+// score and the number of words in it
+fn lexicon_phrase(lexicon: &Lexicon, text: &str, spans: &[(usize, usize)]) -> Option<(f32, usize)> {
+    let longest = lexicon.longest_phrase().min(spans.len());
+    for len in (1..=longest).rev() {
+        let words = &spans[..len];
+        // the words of a phrase must only be separated by whitespace
+        let separated_by_whitespace = words
+            .windows(2)
+            .all(|pair| text[pair[0].1..pair[1].0].trim().is_empty());
+        if !separated_by_whitespace {
+            continue;
+        }
+        let phrase = words
+            .iter()
+            .map(|&(start, end)| &text[start..end])
+            .collect::<Vec<_>>()
+            .join(" ");
+        if let Some(score) = lexicon.get(&phrase) {
+            return Some((score, len));
+        }
+    }
+    None
+}
+
+/// The sentiment of a piece of text.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Sentiment {
+    /// The positivity score of the text.
+    pub positive: f32,
+    /// The negativity score of the text. This is never less than zero.
+    pub negative: f32,
+    /// Every token of the text, in the order in which they appear.
+    pub tokens: Vec<Token>,
+}
+
+impl Sentiment {
+    /// Create a sentiment whose positivity and negativity scores are the sums of the
+    /// positive and negative token scores respectively.
+    pub fn from_tokens(tokens: Vec<Token>) -> Self {
+        let positive = tokens
+            .iter()
+            .filter(|t| t.score > 0.0)
+            .fold(0.0, |sum, t| sum + t.score);
+        let negative = tokens
+            .iter()
+            .filter(|t| t.score < 0.0)
+            .fold(0.0, |sum, t| sum - t.score);
+        Self {
+            positive,
+            negative,
+            tokens,
+        }
+    }
+    pub(crate) fn get_polarity(&self, thresholds: &Thresholds) -> i8 {
+        let net = self.positive - self.negative;
+        if net.abs() < f32::EPSILON || thresholds.is_neutral(net, self.tokens.len()) {
+            0
+        } else if net > 0.0 {
+            1
+        } else {
+            -1
+        }
+    }
+}
+
+/// The thresholds that decide when a [`Sentiment`](struct.Sentiment.html) is neutral. By
+/// default, only a sentiment whose positivity and negativity scores are equal is neutral.
+#[derive(Debug, PartialEq, Clone, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default, rename_all = "kebab-case")
+)]
+pub struct Thresholds {
+    /// A sentiment is neutral if the difference between its positivity and negativity
+    /// scores is no larger than this.
+    pub neutral_band: f32,
+    /// A sentiment is neutral if the difference between its positivity and negativity
+    /// scores, divided by its number of tokens, is no larger than this. This keeps a long
+    /// text with a single mildly positive word neutral.
+    pub neutral_ratio: f32,
+}
+
+impl Thresholds {
+    // whether the net score `net` of a sentiment with `tokens` tokens is within the bands
+    fn is_neutral(&self, net: f32, tokens: usize) -> bool {
+        let net = net.abs();
+        net <= self.neutral_band || (tokens > 0 && net / tokens as f32 <= self.neutral_ratio)
+    }
+}
+
+/// A single token of the analysed text along with its own score.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Token {
+    /// The token exactly as it appears in the text. This is used to find the token in the
+    /// output when coloring words separately.
+    pub text: String,
+    /// The score of this token, positive tokens have a score greater than zero and negative
+    /// tokens a score less than zero.
+    pub score: f32,
+}
+
+impl Token {
+    /// Create a new token.
+    pub fn new(text: &str, score: f32) -> Self {
+        Self {
+            text: text.to_string(),
+            score,
+        }
+    }
+}
+
+/// Split `string` into its words. A word is a run of alphanumeric characters, which may
+/// contain single apostrophes or hyphens (e.g. `don't` or `well-known`). Emoji and ASCII
+/// emoticons (e.g. `:)`) are tokens of their own.
+pub fn tokenize(string: &str) -> Vec<&str> {
+    token_spans(string)
+        .into_iter()
+        .map(|(start, end)| &string[start..end])
+        .collect()
+}
+
+// the byte ranges of the words in `string`, as found by `tokenize`
+fn token_spans(string: &str) -> Vec<(usize, usize)> {
+    let mut spans = vec![];
+    let mut start = None;
+    let mut chars = string.char_indices().peekable();
+    while let Some((index, c)) = chars.next() {
+        let symbol_end = if start.is_none() {
+            emoji::emoticon_len(string, index).map(|len| index + len)
+        } else {
+            None
+        };
+        let symbol_end = match symbol_end {
+            Some(end) => Some(end),
+            None if emoji::is_emoji(c) => Some(emoji::emoji_end(string, index)),
+            None => None,
+        };
+        if let Some(end) = symbol_end {
+            if let Some(s) = start.take() {
+                spans.push((s, index));
+            }
+            spans.push((index, end));
+            while chars.peek().is_some_and(|&(next, _)| next < end) {
+                chars.next();
+            }
+            continue;
+        }
+        let joins_word = (c == '\'' || c == '-')
+            && start.is_some()
+            && chars.peek().is_some_and(|(_, next)| next.is_alphanumeric());
+        if c.is_alphanumeric() || joins_word {
+            start.get_or_insert(index);
+        } else if let Some(s) = start.take() {
+            spans.push((s, index));
+        }
+    }
+    if let Some(s) = start {
+        spans.push((s, string.len()));
+    }
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize_keeps_joined_words_together() {
+        assert_eq!(
+            tokenize("Don't   self-destruct -- it's 2 o'clock!"),
+            vec!["Don't", "self-destruct", "it's", "2", "o'clock"]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_finds_emoji_and_emoticons() {
+        assert_eq!(
+            tokenize("Deployed✅ :) but 👎🏽\u{fe0f}, see https://ci :("),
+            vec![
+                "Deployed",
+                "✅",
+                ":)",
+                "but",
+                "👎🏽\u{fe0f}",
+                "see",
+                "https",
+                "ci",
+                ":("
+            ]
+        );
+    }
+
+    #[test]
+    fn test_afinn_scores_emoji_from_the_lexicon_first() {
+        let mut lexicon = Lexicon::new();
+        lexicon.insert("🎉", -1.0);
+        let analyzer = Afinn::with_lexicon(lexicon);
+        assert_eq!(
+            analyzer.analyze("🎉 ❌ :)").tokens,
+            vec![
+                Token::new("🎉", -1.0),
+                Token::new("❌", -2.0),
+                Token::new(":)", 2.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_afinn_scores_every_token() {
+        assert_eq!(
+            Afinn::default().analyze("Zealous, but bad"),
+            Sentiment {
+                positive: 1.0,
+                negative: 4.5,
+                tokens: vec![
+                    Token::new("Zealous", 1.0),
+                    Token::new("but", 0.0),
+                    Token::new("bad", -4.5),
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn test_afinn_accounts_for_negations() {
+        let sentiment = Afinn::default().analyze("This is not good");
+        assert_eq!(sentiment.get_polarity(&Thresholds::default()), -1);
+    }
+
+    #[test]
+    fn test_afinn_uses_the_bundled_lexicons() {
+        let analyzer = Afinn::default().language(Language::Auto);
+        assert_eq!(
+            analyzer
+                .analyze("Der Test ist fehlgeschlagen")
+                .get_polarity(&Thresholds::default()),
+            -1
+        );
+        assert_eq!(
+            analyzer
+                .analyze("El despliegue fue un éxito")
+                .get_polarity(&Thresholds::default()),
+            1
+        );
+        let analyzer = Afinn::default().language(Language::French);
+        assert_eq!(
+            analyzer
+                .analyze("Ce n'est pas bon")
+                .get_polarity(&Thresholds::default()),
+            -1
+        );
+    }
+
+    #[test]
+    fn test_afinn_prefers_the_lexicon() {
+        let mut lexicon = Lexicon::new();
+        lexicon.insert("green build", 3.0);
+        lexicon.insert("bad", 1.0);
+        assert_eq!(
+            Afinn::with_lexicon(lexicon)
+                .analyze("Green  build, then green. Build bad")
+                .tokens,
+            vec![
+                Token::new("Green  build", 3.0),
+                Token::new("then", 0.0),
+                Token::new("green", 0.0),
+                Token::new("Build", 0.0),
+                Token::new("bad", 1.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sentiment_from_tokens_sums_the_scores() {
+        let sentiment = Sentiment::from_tokens(vec![
+            Token::new("good", 3.0),
+            Token::new("bad", -3.0),
+            Token::new("worse", -3.0),
+        ]);
+        assert_eq!((sentiment.positive, sentiment.negative), (3.0, 6.0));
+        assert_eq!(sentiment.get_polarity(&Thresholds::default()), -1);
+    }
+
+    #[test]
+    fn test_thresholds_widen_the_neutral_band() {
+        let mut tokens = vec![Token::new("good", 2.0)];
+        tokens.extend((0..9).map(|_| Token::new("word", 0.0)));
+        let sentiment = Sentiment::from_tokens(tokens);
+        assert_eq!(sentiment.get_polarity(&Thresholds::default()), 1);
+        let band = Thresholds {
+            neutral_band: 2.0,
+            ..Thresholds::default()
+        };
+        assert_eq!(sentiment.get_polarity(&band), 0);
+        let ratio = Thresholds {
+            neutral_ratio: 0.2,
+            ..Thresholds::default()
+        };
+        assert_eq!(sentiment.get_polarity(&ratio), 0);
+        let ratio = Thresholds {
+            neutral_ratio: 0.15,
+            ..Thresholds::default()
+        };
+        assert_eq!(sentiment.get_polarity(&ratio), 1);
+    }
+}
@@ -1,6 +1,7 @@
 //! This module is responsible for building the command line application and it's
 //! associated interface.
 use clap::{self, crate_authors, crate_version, App, AppSettings};
+use std::sync::OnceLock;
 
 /// Arg is a light alias for a clap::Arg that is specialized to compile time
 /// string literals.
@@ -31,6 +32,17 @@ pub struct CustomArg {
     pub doc_long: &'static str,
     /// Whether this flag is hidden or not.
     pub hidden: bool,
+    /// The environment variable that supplies the default value of this argument, if any.
+    /// For a switch, the variable enables it if it is set to `1`, `true`, `yes` or `on`, and
+    /// disables it if it is set to `0`, `false`, `no` or `off`.
+    pub env: Option<&'static str>,
+    /// The names of the arguments that this one cannot be combined with, because it
+    /// conflicts with them or overrides them.
+    pub conflicts: Vec<&'static str>,
+    /// The long name of the hidden switch that turns this switch off again, e.g.
+    /// `no-gradient`, if any. This is how a switch that is enabled by default (e.g. by the
+    /// configuration file) is disabled on the command line.
+    pub negation: Option<&'static str>,
     /// The type of this argument.
     pub kind: CustomArgKind,
 }
@@ -102,6 +114,9 @@ impl CustomArg {
             doc_short: "",
             doc_long: "",
             hidden: false,
+            env: None,
+            conflicts: vec![],
+            negation: None,
             kind: CustomArgKind::Positional {
                 value_name,
                 multiple: false,
@@ -125,6 +140,9 @@ impl CustomArg {
             doc_short: "",
             doc_long: "",
             hidden: false,
+            env: None,
+            conflicts: vec![],
+            negation: None,
             kind: CustomArgKind::Switch {
                 long: long_name,
                 short: None,
@@ -147,14 +165,16 @@ impl CustomArg {
         let clap_arg = Arg::with_name(long_name)
             .long(long_name)
             .value_name(value_name)
-            .takes_value(true)
-            .number_of_values(1);
+            .takes_value(true);
         Self {
             clap_arg,
             name: long_name,
             doc_short: "",
             doc_long: "",
             hidden: false,
+            env: None,
+            conflicts: vec![],
+            negation: None,
             kind: CustomArgKind::Flag {
                 long: long_name,
                 short: None,
@@ -230,12 +250,31 @@ impl CustomArg {
                 ref mut multiple, ..
             } => {
                 *multiple = true;
+                // without this, the values following the flag would be taken as its values
+                // instead of the positional arguments they are
+                self.clap_arg = self.clap_arg.number_of_values(1);
             }
         }
         self.clap_arg = self.clap_arg.multiple(true);
         self
     }
 
+    /// Allow multiple values separated by commas to be given to this flag at once, e.g.,
+    /// `--flag a,b`. This implies [`multiple`](#method.multiple).
+    ///
+    /// This panics if this arg is not a flag.
+    fn delimited(mut self) -> Self {
+        match self.kind {
+            CustomArgKind::Positional { .. } => panic!("expected flag"),
+            CustomArgKind::Switch { .. } => panic!("expected flag"),
+            CustomArgKind::Flag { .. } => {
+                self = self.multiple();
+                self.clap_arg = self.clap_arg.use_delimiter(true);
+            }
+        }
+        self
+    }
+
     /// Set the possible values for this argument. If this argument is not
     /// a flag, then this panics.
     ///
@@ -271,6 +310,34 @@ impl CustomArg {
         self
     }
 
+    /// Set the environment variable that supplies the default value of this argument. The
+    /// value of the variable is used unless the argument, or one that conflicts with it, is
+    /// supplied on the command line.
+    ///
+    /// This panics if this arg is positional.
+    fn env(mut self, name: &'static str) -> Self {
+        if let CustomArgKind::Positional { .. } = self.kind {
+            panic!("expected switch or flag");
+        }
+        self.env = Some(name);
+        self
+    }
+
+    /// Add a hidden switch called `long` that turns this switch off again. Whichever of the
+    /// two is supplied last wins.
+    ///
+    /// This panics if this arg is not a switch.
+    fn negated(mut self, long: &'static str) -> Self {
+        match self.kind {
+            CustomArgKind::Switch { .. } => {
+                self.clap_arg = self.clap_arg.overrides_with(long);
+                self.negation = Some(long);
+            }
+            _ => panic!("expected switch"),
+        }
+        self
+    }
+
     /// Hide this flag from all documentation.
     #[allow(dead_code)]
     fn hidden(mut self) -> Self {
@@ -308,15 +375,16 @@ impl CustomArg {
     /// an error.
     fn conflicts(mut self, names: &[&'static str]) -> Self {
         self.clap_arg = self.clap_arg.conflicts_with_all(names);
+        self.conflicts.extend_from_slice(names);
         self
     }
 
     /// Sets an overriding argument. That is, if this argument and the given
     /// argument are both provided by an end user, then the "last" one will
     /// win.
-    #[allow(dead_code)]
     fn overrides(mut self, name: &'static str) -> Self {
         self.clap_arg = self.clap_arg.overrides_with(name);
+        self.conflicts.push(name);
         self
     }
 
@@ -347,6 +415,17 @@ impl CustomArg {
         });
         self
     }
+
+    /// Indicate that any value given to this argument should be a non-negative decimal
+    /// number. If it's not, then clap will report an error to the end user.
+    fn decimal(mut self) -> Self {
+        self.clap_arg = self.clap_arg.validator(|val| match val.parse::<f32>() {
+            Ok(number) if number >= 0.0 => Ok(()),
+            Ok(_) => Err("the number must not be negative".to_string()),
+            Err(err) => Err(err.to_string()),
+        });
+        self
+    }
 }
 
 // We add an extra space to long descriptions so that a blank line is inserted
@@ -381,9 +460,42 @@ pub fn app() -> App<'static, 'static> {
         .setting(AppSettings::AllArgsOverrideSelf)
         .help_message("Prints help information. Use --help for more details.");
     for arg in all_args_and_flags() {
+        if let Some(negation) = arg.negation {
+            let negation = Arg::with_name(negation)
+                .long(negation)
+                .hidden(true)
+                .overrides_with(arg.name);
+            app = app.arg(negation);
+        }
         app = app.arg(arg.clap_arg);
     }
-    app
+    app.after_help(environment())
+}
+
+// the list of environment variables shown after the help, which is built only once since
+// clap only borrows the text
+fn environment() -> &'static str {
+    static ENVIRONMENT: OnceLock<String> = OnceLock::new();
+    ENVIRONMENT.get_or_init(|| {
+        let mut environment = String::from("ENVIRONMENT:");
+        for arg in all_args_and_flags() {
+            if let (Some(env), false) = (arg.env, arg.hidden) {
+                environment.push_str(&format!("\n    {:<36}{}", env, long_name(&arg.kind)));
+            }
+        }
+        environment
+    })
+}
+
+/// The long name of a switch or a flag with the leading dashes, e.g. `--color`, or the
+/// name of the value of a positional argument.
+pub fn long_name(kind: &CustomArgKind) -> String {
+    match kind {
+        CustomArgKind::Positional { value_name, .. } => value_name.to_string(),
+        CustomArgKind::Switch { long, .. } | CustomArgKind::Flag { long, .. } => {
+            format!("--{}", long)
+        }
+    }
 }
 
 /// This function takes a vector as an argument and pushes all the arguments for the app
@@ -397,9 +509,29 @@ pub fn all_args_and_flags() -> Vec<CustomArg> {
     switch_disable_interpretation(&mut args);
     switch_plain(&mut args);
     switch_error(&mut args);
+    switch_lines(&mut args);
+    switch_words(&mut args);
+    switch_explain(&mut args);
+    switch_gradient(&mut args);
+    switch_no_config(&mut args);
     flag_input_file(&mut args);
     flag_color(&mut args);
+    flag_color_mode(&mut args);
+    flag_theme(&mut args);
+    flag_background(&mut args);
     flag_formatting(&mut args);
+    flag_lexicon(&mut args);
+    flag_lang(&mut args);
+    flag_explain_file(&mut args);
+    flag_neutral_band(&mut args);
+    flag_neutral_ratio(&mut args);
+    flag_tiers(&mut args);
+    flag_strong_threshold(&mut args);
+    flag_positive_color(&mut args);
+    flag_negative_color(&mut args);
+    flag_neutral_color(&mut args);
+    flag_strongly_positive_color(&mut args);
+    flag_strongly_negative_color(&mut args);
     args
 }
 
@@ -411,6 +543,9 @@ A string that will be printed to stdout (or stderr using --error flag).
 This can be left empty, in which case, a newline will be printed
 (assuming the -n/--newline flag is not supplied).
 
+If no STRING is supplied and stdin is not a terminal (for example when
+printr is at the end of a pipe), the input STRING is read from stdin.
+
 Example:
 
     `printr Hello` will output \"Hello\"
@@ -434,6 +569,8 @@ When this switch is specified, the output does not have a newline
         "
     );
     let arg = CustomArg::switch("newline")
+        .negated("no-newline")
+        .env("PRINTR_NEWLINE")
         .short("n")
         .help(SHORT)
         .long_help(LONG);
@@ -456,6 +593,8 @@ This switch has no effect on the output if an input file
         "
     );
     let arg = CustomArg::switch("spaces")
+        .negated("no-spaces")
+        .env("PRINTR_SPACES")
         .short("s")
         .help(SHORT)
         .long_help(LONG);
@@ -470,6 +609,8 @@ This is the default behavior.
         "
     );
     let arg = CustomArg::switch("disable_interpretation")
+        .overrides("enable_interpretation")
+        .env("PRINTR_DISABLE_INTERPRETATION")
         .short("E")
         .help(SHORT)
         .long_help(LONG);
@@ -482,7 +623,7 @@ fn switch_enable_interpretation(args: &mut Vec<CustomArg>) {
         "\
 If -e is used, the following sequences are recognized:
 
-    • \\ backslash
+    • \\\\ backslash
 
     • \\a alert (BEL)
 
@@ -508,6 +649,8 @@ If -e is used, the following sequences are recognized:
         "
     );
     let arg = CustomArg::switch("enable_interpretation")
+        .overrides("disable_interpretation")
+        .env("PRINTR_ENABLE_INTERPRETATION")
         .short("e")
         .help(SHORT)
         .long_help(LONG);
@@ -524,6 +667,8 @@ color escape codes do not mangle up the resulting file.
         "
     );
     let arg = CustomArg::switch("plain")
+        .negated("no-plain")
+        .env("PRINTR_PLAIN")
         .short("p")
         .help(SHORT)
         .long_help(LONG);
@@ -542,7 +687,134 @@ Example:
         printr --error \"Error string input\" 2> errors.txt
         "
     );
-    let arg = CustomArg::switch("error").help(SHORT).long_help(LONG);
+    let arg = CustomArg::switch("error")
+        .negated("no-error")
+        .env("PRINTR_ERROR")
+        .help(SHORT)
+        .long_help(LONG);
+    args.push(arg);
+}
+
+fn switch_lines(args: &mut Vec<CustomArg>) {
+    const SHORT: &str = "Color every line of the input separately";
+    const LONG: &str = long!(
+        "\
+When this switch is specified, the input is read and printed one line
+at a time. The sentiment (and hence the color) of each line is guessed
+separately, and each line is printed as soon as it has been read. This
+makes printr usable as a live log colorizer.
+
+Example:
+
+    tail -f server.log | printr --lines
+        "
+    );
+    let arg = CustomArg::switch("lines")
+        .negated("no-lines")
+        .env("PRINTR_LINES")
+        .short("l")
+        .help(SHORT)
+        .long_help(LONG);
+    args.push(arg);
+}
+
+fn switch_words(args: &mut Vec<CustomArg>) {
+    const SHORT: &str = "Color every word by its own sentiment";
+    const LONG: &str = long!(
+        "\
+When this switch is specified, every word is colored by its own
+sentiment score instead of coloring the whole output by the overall
+sentiment. Positive words are printed in the positive color (green by
+default), negative words in the negative color (red by default) and
+everything else is left plain. This shows why a STRING was guessed to
+be positive or negative.
+        "
+    );
+    let arg = CustomArg::switch("words")
+        .negated("no-words")
+        .env("PRINTR_WORDS")
+        .short("w")
+        .help(SHORT)
+        .long_help(LONG)
+        .conflicts(&["color", "plain"]);
+    args.push(arg);
+}
+
+fn switch_explain(args: &mut Vec<CustomArg>) {
+    const SHORT: &str = "Explain the guessed sentiment on stderr";
+    const LONG: &str = long!(
+        "\
+When this switch is specified, a JSON report explaining how the color
+of the output was chosen is printed to stderr. It contains the positive
+and negative scores, the polarity, every word that contributed to the
+sentiment along with its weight and the chosen color. The output itself
+is not changed. With --lines, a report is printed for every line.
+
+Use --explain-file to write the report to a file instead.
+        "
+    );
+    let arg = CustomArg::switch("explain")
+        .negated("no-explain")
+        .env("PRINTR_EXPLAIN")
+        .help(SHORT)
+        .long_help(LONG);
+    args.push(arg);
+}
+
+fn switch_gradient(args: &mut Vec<CustomArg>) {
+    const SHORT: &str = "Color the output on a scale from red to green";
+    const LONG: &str = long!(
+        "\
+When this switch is specified, the color of the output is interpolated
+from the net sentiment score of the input (its positive minus its
+negative score) instead of being one of a few fixed colors. Neutral
+input is printed in a pale gray, mildly positive input in a pale green
+and strongly positive input in a vivid green, and likewise in red for
+negative input. This overrides the colors of the sentiment tiers.
+
+This requires a terminal emulator with 24-bit (truecolor) support.
+        "
+    );
+    let arg = CustomArg::switch("gradient")
+        .negated("no-gradient")
+        .env("PRINTR_GRADIENT")
+        .help(SHORT)
+        .long_help(LONG)
+        .conflicts(&["color", "plain", "words"]);
+    args.push(arg);
+}
+
+fn switch_no_config(args: &mut Vec<CustomArg>) {
+    const SHORT: &str = "Do not read the configuration file";
+    const LONG: &str = long!(
+        "\
+By default, printr reads default values for its switches and flags from
+$XDG_CONFIG_HOME/printr/config.toml (or ~/.config/printr/config.toml),
+or from the file whose path is in the PRINTR_CONFIG environment variable.
+When this switch is specified, no configuration file is read.
+
+The file is in the TOML format and its keys are the long names of the
+switches and flags. Switches take true or false, flags take a string or
+a number, or an array of them if they can be supplied multiple times.
+The values in the file are only defaults: they are ignored for the
+switches and flags supplied on the command line and for the ones that
+conflict with them, except for flags that can be supplied multiple times,
+whose values are combined. A switch enabled in the file is disabled with
+--no-SWITCH, e.g. --no-gradient. Relative paths in the file, e.g. of
+--lexicon, are relative to the directory of the file.
+
+Example:
+
+    color-mode = \"always\"
+    formatting = [\"bold\"]
+    lexicon = [\"/home/me/ci-words.tsv\"]
+    neutral-band = 1.5
+        "
+    );
+    let arg = CustomArg::switch("no-config")
+        .env("PRINTR_NO_CONFIG")
+        .help(SHORT)
+        .long_help(LONG);
     args.push(arg);
 }
 
@@ -551,10 +823,17 @@ fn flag_input_file(args: &mut Vec<CustomArg>) {
     const LONG: &str = long!(
         "\
 If this is supplied, the input STRING is read from the file
-contents.
+contents. Use - to read from stdin. A single trailing newline of
+the contents is stripped, so that the output does not end with an
+extra blank line.
+
+Example:
+
+    git log -1 --format=%s | printr -i -
         "
     );
     let arg = CustomArg::flag("input-file", "input-file")
+        .env("PRINTR_INPUT_FILE")
         .short("i")
         .help(SHORT)
         .long_help(LONG)
@@ -568,31 +847,383 @@ fn flag_color(args: &mut Vec<CustomArg>) {
         "\
 If not supplied, the color is guessed from the contents of the input
 using some naive sentiment analysis.
-[possible values: red, blue, green, yellow, cyan]
+
+The color can be one of the names black, red, green, yellow, blue,
+magenta, cyan and white, a number from 0 to 255 which selects a color of
+the 256 color palette, or a 24-bit color given as a hex code or by its
+RGB components. Not every terminal emulator supports the latter two.
+
+Example:
+
+    printr --color '#ff8800' \"Deploying\"
+    printr --color 'rgb(255, 136, 0)' \"Deploying\"
+    printr --color 208 \"Deploying\"
         "
     );
-    let arg = CustomArg::flag("color", "color")
+    let arg = CustomArg::flag("color", "COLOR")
+        .env("PRINTR_COLOR")
         .short("c")
         .help(SHORT)
         .long_help(LONG)
-        .conflicts(&["plain"])
-        .possible_values(&["red", "blue", "green", "yellow", "cyan"]);
+        .conflicts(&["plain"]);
+    args.push(arg);
+}
+
+fn flag_color_mode(args: &mut Vec<CustomArg>) {
+    const SHORT: &str = "When to color and format the output, defaults to `auto`";
+    const LONG: &str = long!(
+        "\
+With auto, the output is only colored and formatted when it is written
+to a terminal (stdout, or stderr with --error), so that redirecting it to
+a file does not fill the file with escape sequences. With always or
+never, the output is always or never colored and formatted.
+
+In auto mode, the following environment variables are honored, in order
+of precedence: CLICOLOR_FORCE set to anything but 0 always styles the
+output, NO_COLOR set to anything non-empty never does, and neither do
+CLICOLOR=0 and TERM=dumb.
+[possible values: auto, always, never]
+        "
+    );
+    let arg = CustomArg::flag("color-mode", "WHEN")
+        .env("PRINTR_COLOR_MODE")
+        .help(SHORT)
+        .long_help(LONG)
+        .possible_values(&["auto", "always", "never"]);
+    args.push(arg);
+}
+
+fn flag_theme(args: &mut Vec<CustomArg>) {
+    const SHORT: &str = "The theme that determines how the output looks";
+    const LONG: &str = long!(
+        "\
+A theme bundles the colors of the sentiment tiers, the formatting of the
+output and a prefix (such as an icon) that is put before the output.
+The switches and flags supplied on the command line take precedence
+over the ones of the theme.
+
+The built-in themes are:
+
+    default        green, blue and red
+    solarized      five tiers in the colors of the Solarized palette
+    high-contrast  bold and vivid colors, with an icon before the output
+    monochrome     no colors, only [+], [=] or [-] before the output
+
+Themes can also be defined in TOML files in the themes directory of the
+configuration directory, e.g. ~/.config/printr/themes/NAME.toml, which
+take precedence over the built-in ones. Such a file can contain the keys
+tiers, strong-threshold and formatting (which work like the flags of the
+same names), colored (false for no colors), prefix (in which {icon} and
+{tier} are replaced by the icon and the name of the sentiment tier) and
+the tables colors and icons.
+
+Example:
+
+    tiers = 5
+    prefix = \"{icon} \"
+
+    [colors]
+    positive = \"#2aa198\"
+
+    [icons]
+    positive = \"✔\"
+    neutral = \"•\"
+    negative = \"✖\"
+        "
+    );
+    let arg = CustomArg::flag("theme", "NAME")
+        .env("PRINTR_THEME")
+        .help(SHORT)
+        .long_help(LONG);
+    args.push(arg);
+}
+
+fn flag_background(args: &mut Vec<CustomArg>) {
+    const SHORT: &str = "The background color of the output";
+    const LONG: &str = long!(
+        "\
+The output is displayed on this background, which can be any of the
+colors described in --color. If this is sentiment, the background is the
+color guessed from the contents of the input instead, and the text is
+black unless --color is supplied. This makes alerts stand out.
+
+Example:
+
+    printr -b sentiment \"Deployment failed\"
+    printr -b '#303030' -c white \"Deploying\"
+        "
+    );
+    let arg = CustomArg::flag("background", "COLOR")
+        .env("PRINTR_BACKGROUND")
+        .short("b")
+        .help(SHORT)
+        .long_help(LONG)
+        .conflicts(&["plain", "words"]);
     args.push(arg);
 }
 
 fn flag_formatting(args: &mut Vec<CustomArg>) {
-    const SHORT: &str = "The style to apply to the text, defaults to `None`";
+    const SHORT: &str = "The styles to apply to the text, defaults to `None`";
     const LONG: &str = long!(
         "\
-Some terminal emulators do not support some of the formatting options.
-You might have to experiment to find which ones work for you.
-[possible values: bold, underline, strikethrough, dimmed]
+Several styles can be combined by separating them with commas or by
+supplying this flag multiple times. Some terminal emulators do not
+support some of the formatting options. You might have to experiment to
+find which ones work for you.
+[possible values: bold, dimmed, italic, underline, blink, reverse, hidden,
+strikethrough]
+
+Example:
+
+    printr -f bold,italic,underline \"Release notes\"
         "
     );
     let arg = CustomArg::flag("formatting", "formatting")
+        .env("PRINTR_FORMATTING")
         .short("f")
         .help(SHORT)
         .long_help(LONG)
-        .possible_values(&["bold", "underline", "strikethrough", "dimmed"]);
+        .delimited()
+        .possible_values(&[
+            "bold",
+            "dimmed",
+            "italic",
+            "underline",
+            "blink",
+            "reverse",
+            "hidden",
+            "strikethrough",
+        ]);
+    args.push(arg);
+}
+
+fn flag_lexicon(args: &mut Vec<CustomArg>) {
+    const SHORT: &str = "A file with extra words to use for the sentiment analysis";
+    const LONG: &str = long!(
+        "\
+The words (or phrases) in this file are scored before the default AFINN
+lexicon, so they can be used to add new words or to override the score
+of existing ones. This includes emoji and emoticons such as :) which are
+scored from a built-in table by default. This flag can be supplied
+multiple times, in which case the later files take precedence.
+
+A file in the TSV format contains a word (or phrase) and its score
+(usually between -5 and 5) separated by a tab on each line. Empty lines
+and lines starting with # are ignored. A file in the JSON format contains
+a single object mapping the words to their scores.
+
+Example:
+
+    flaky\t-2
+    green build\t3
+
+    {\"regression\": -3, \"green build\": 3}
+        "
+    );
+    let arg = CustomArg::flag("lexicon", "FILE")
+        .env("PRINTR_LEXICON")
+        .multiple()
+        .help(SHORT)
+        .long_help(LONG);
+    args.push(arg);
+}
+
+fn flag_lang(args: &mut Vec<CustomArg>) {
+    const SHORT: &str = "The language of the input, defaults to `en`";
+    const LONG: &str = long!(
+        "\
+The language the sentiment analysis is performed in. English is scored
+using the AFINN lexicon, the other languages use smaller lexicons that
+are bundled with printr. With auto, the language of the input is
+detected (from its most common words) before it is analysed.
+[possible values: auto, en, de, es, fr]
+        "
+    );
+    let arg = CustomArg::flag("lang", "LANG")
+        .env("PRINTR_LANG")
+        .help(SHORT)
+        .long_help(LONG)
+        .possible_values(&["auto", "en", "de", "es", "fr"]);
+    args.push(arg);
+}
+
+fn flag_explain_file(args: &mut Vec<CustomArg>) {
+    const SHORT: &str = "Write the --explain report to a file";
+    const LONG: &str = long!(
+        "\
+If this is supplied, the report described in --explain is written to
+this file (which is overwritten) instead of stderr.
+        "
+    );
+    let arg = CustomArg::flag("explain-file", "FILE")
+        .env("PRINTR_EXPLAIN_FILE")
+        .help(SHORT)
+        .long_help(LONG);
+    args.push(arg);
+}
+
+fn flag_neutral_band(args: &mut Vec<CustomArg>) {
+    const SHORT: &str = "The net score within which the input is neutral";
+    const LONG: &str = long!(
+        "\
+By default, the input is only guessed to be neutral (and printed in
+blue) when its positive and negative scores are exactly equal. If this
+is supplied, the input is also neutral when the difference between the
+scores is no larger than this.
+
+Example:
+
+    printr --neutral-band 1.5 \"Fixed a typo, great\"
+        "
+    );
+    let arg = CustomArg::flag("neutral-band", "NUM")
+        .env("PRINTR_NEUTRAL_BAND")
+        .help(SHORT)
+        .long_help(LONG)
+        .decimal();
+    args.push(arg);
+}
+
+fn flag_neutral_ratio(args: &mut Vec<CustomArg>) {
+    const SHORT: &str = "The net score per word within which the input is neutral";
+    const LONG: &str = long!(
+        "\
+If this is supplied, the input is neutral when the difference between
+its positive and negative scores, divided by its number of words, is no
+larger than this. Unlike --neutral-band, this scales with the length of
+the input, so that a single mildly positive word does not color a long
+text green. Both flags can be combined.
+        "
+    );
+    let arg = CustomArg::flag("neutral-ratio", "NUM")
+        .env("PRINTR_NEUTRAL_RATIO")
+        .help(SHORT)
+        .long_help(LONG)
+        .decimal();
+    args.push(arg);
+}
+
+fn flag_tiers(args: &mut Vec<CustomArg>) {
+    const SHORT: &str = "The number of sentiment tiers, defaults to 3";
+    const LONG: &str = long!(
+        "\
+By default, the guessed sentiment is either positive (green), neutral
+(blue) or negative (red). With 5 tiers, input whose net score is at
+least --strong-threshold away from zero is strongly positive or strongly
+negative, and the tiers are colored green, cyan, blue, yellow and red
+from strongly positive to strongly negative.
+
+Supplying --strong-threshold or the color of a strong tier implies 5
+tiers.
+[possible values: 3, 5]
+        "
+    );
+    let arg = CustomArg::flag("tiers", "NUM")
+        .env("PRINTR_TIERS")
+        .help(SHORT)
+        .long_help(LONG)
+        .possible_values(&["3", "5"]);
+    args.push(arg);
+}
+
+fn flag_strong_threshold(args: &mut Vec<CustomArg>) {
+    const SHORT: &str = "The net score of strongly positive or negative input";
+    const LONG: &str = long!(
+        "\
+The difference between the positive and negative scores from which the
+input is strongly positive or strongly negative when 5 tiers are used.
+This defaults to 4.
+        "
+    );
+    let arg = CustomArg::flag("strong-threshold", "NUM")
+        .env("PRINTR_STRONG_THRESHOLD")
+        .help(SHORT)
+        .long_help(LONG)
+        .decimal();
+    args.push(arg);
+}
+
+fn flag_positive_color(args: &mut Vec<CustomArg>) {
+    const SHORT: &str = "The color of positive input";
+    const LONG: &str = long!(
+        "\
+The color that input which is guessed to be positive is displayed in.
+This defaults to green, or cyan with 5 tiers. Any of the colors
+described in --color can be used.
+
+Example:
+
+    printr --positive-color blue --negative-color yellow \"All tests passed\"
+        "
+    );
+    let arg = CustomArg::flag("positive-color", "COLOR")
+        .env("PRINTR_POSITIVE_COLOR")
+        .help(SHORT)
+        .long_help(LONG)
+        .conflicts(&["color", "plain"]);
+    args.push(arg);
+}
+
+fn flag_negative_color(args: &mut Vec<CustomArg>) {
+    const SHORT: &str = "The color of negative input";
+    const LONG: &str = long!(
+        "\
+The color that input which is guessed to be negative is displayed in.
+This defaults to red, or yellow with 5 tiers.
+        "
+    );
+    let arg = CustomArg::flag("negative-color", "COLOR")
+        .env("PRINTR_NEGATIVE_COLOR")
+        .help(SHORT)
+        .long_help(LONG)
+        .conflicts(&["color", "plain"]);
+    args.push(arg);
+}
+
+fn flag_neutral_color(args: &mut Vec<CustomArg>) {
+    const SHORT: &str = "The color of neutral input";
+    const LONG: &str = long!(
+        "\
+The color that input which is guessed to be neutral is displayed in.
+This defaults to blue.
+        "
+    );
+    let arg = CustomArg::flag("neutral-color", "COLOR")
+        .env("PRINTR_NEUTRAL_COLOR")
+        .help(SHORT)
+        .long_help(LONG)
+        .conflicts(&["color", "plain"]);
+    args.push(arg);
+}
+
+fn flag_strongly_positive_color(args: &mut Vec<CustomArg>) {
+    const SHORT: &str = "The color of strongly positive input";
+    const LONG: &str = long!(
+        "\
+The color that input which is guessed to be strongly positive is
+displayed in when 5 tiers are used. This defaults to green.
+        "
+    );
+    let arg = CustomArg::flag("strongly-positive-color", "COLOR")
+        .env("PRINTR_STRONGLY_POSITIVE_COLOR")
+        .help(SHORT)
+        .long_help(LONG)
+        .conflicts(&["color", "plain"]);
+    args.push(arg);
+}
+
+fn flag_strongly_negative_color(args: &mut Vec<CustomArg>) {
+    const SHORT: &str = "The color of strongly negative input";
+    const LONG: &str = long!(
+        "\
+The color that input which is guessed to be strongly negative is
+displayed in when 5 tiers are used. This defaults to red.
+        "
+    );
+    let arg = CustomArg::flag("strongly-negative-color", "COLOR")
+        .env("PRINTR_STRONGLY_NEGATIVE_COLOR")
+        .help(SHORT)
+        .long_help(LONG)
+        .conflicts(&["color", "plain"]);
     args.push(arg);
 }
@@ -0,0 +1,408 @@
+//! This module is responsible for loading the configuration file, which supplies default
+//! values for the switches and flags of the command line application.
+//!
+//! The file is in the TOML format and its keys are the long names of the switches and flags,
+//! e.g.
+//!
+//! ```toml
+//! color-mode = "always"
+//! formatting = ["bold", "italic"]
+//! lexicon = ["/home/me/ci-words.tsv"]
+//! neutral-band = 1.5
+//! tiers = 5
+//! positive-color = "blue"
+//! gradient = false
+//! ```
+//!
+//! The values are only defaults (refer to [`Options`](struct.Options.html)): they are ignored
+//! for every switch and flag that is supplied on the command line, or that conflicts with
+//! one that is. A switch that the file enables is disabled again by its `--no-` counterpart,
+//! e.g. `--no-gradient`. Relative paths, e.g. of `lexicon`, are relative to the directory of
+//! the file.
+//!
+//! Every switch and flag can also be supplied by an environment variable, e.g. `PRINTR_COLOR`
+//! for `--color` (refer to [`env_args`](fn.env_args.html)). These are defaults as well, which
+//! take precedence over the configuration file, but not over the command line.
+use crate::{
+    app::{all_args_and_flags, app, CustomArg, CustomArgKind},
+    STDIN,
+};
+use clap::ArgMatches;
+use std::{
+    collections::HashMap,
+    env,
+    fs::read_to_string,
+    io::{self, ErrorKind},
+    iter,
+    path::{Path, PathBuf},
+};
+use toml::Value;
+
+/// The environment variable that overrides the path of the configuration file.
+pub const PRINTR_CONFIG: &str = "PRINTR_CONFIG";
+
+/// The path of the configuration file. This is the value of `PRINTR_CONFIG` if it is set,
+/// otherwise `$XDG_CONFIG_HOME/printr/config.toml` (where `XDG_CONFIG_HOME` defaults to
+/// `$HOME/.config`) if that file exists.
+pub fn path() -> Option<PathBuf> {
+    if let Some(path) = env::var_os(PRINTR_CONFIG).filter(|path| !path.is_empty()) {
+        return Some(PathBuf::from(path));
+    }
+    let path = config_dir()?.join("config.toml");
+    match path.is_file() {
+        true => Some(path),
+        false => None,
+    }
+}
+
+/// The directory in which `printr` looks for its configuration, that is,
+/// `$XDG_CONFIG_HOME/printr` or `$HOME/.config/printr`.
+pub fn config_dir() -> Option<PathBuf> {
+    let base = match env::var_os("XDG_CONFIG_HOME").filter(|dir| !dir.is_empty()) {
+        Some(dir) => PathBuf::from(dir),
+        None => PathBuf::from(env::var_os("HOME")?).join(".config"),
+    };
+    Some(base.join("printr"))
+}
+
+/// Load the configuration file at `path` and return the options it sets. Relative paths in
+/// the file are relative to the directory of the file.
+pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Options> {
+    let path = path.as_ref();
+    let contents = read_to_string(path)?;
+    to_args(&contents, path.parent().unwrap_or_else(|| Path::new("")))
+        .and_then(Options::parse)
+        .map_err(|err| io::Error::new(ErrorKind::InvalidData, err))
+}
+
+/// Parse the contents of a configuration file and return the command line arguments that
+/// its values correspond to, which are used to validate them. Every key must be the long
+/// name of a switch or a flag. A switch takes a boolean, a flag a string or a number, or an
+/// array of them if it can be supplied multiple times.
+///
+/// The relative paths of the flags that take a file, e.g. `lexicon`, are joined to `dir`,
+/// the directory of the configuration file, so that they do not depend on the directory
+/// `printr` is run from.
+pub fn to_args(contents: &str, dir: &Path) -> Result<Vec<String>, String> {
+    let table: toml::Table = contents
+        .parse()
+        .map_err(|err: toml::de::Error| err.message().to_string())?;
+    let known = all_args_and_flags();
+    let mut args = vec![];
+    for (key, value) in &table {
+        let arg = known
+            .iter()
+            .find(|arg| match arg.kind {
+                CustomArgKind::Switch { long, .. } | CustomArgKind::Flag { long, .. } => {
+                    long == key
+                }
+                CustomArgKind::Positional { .. } => false,
+            })
+            .ok_or_else(|| format!("unknown option {:?}", key))?;
+        match (&arg.kind, value) {
+            (CustomArgKind::Switch { .. }, Value::Boolean(true)) => {
+                args.push(format!("--{}", key));
+            }
+            (CustomArgKind::Switch { .. }, Value::Boolean(false)) => {
+                if let Some(negation) = arg.negation {
+                    args.push(format!("--{}", negation));
+                }
+            }
+            (CustomArgKind::Flag { multiple: true, .. }, Value::Array(values)) => {
+                for value in values {
+                    args.push(format!("--{}={}", key, flag_value(key, value, dir)?));
+                }
+            }
+            (CustomArgKind::Flag { .. }, value) => {
+                args.push(format!("--{}={}", key, flag_value(key, value, dir)?));
+            }
+            _ => return Err(format!("expected true or false for {:?}", key)),
+        }
+    }
+    Ok(args)
+}
+
+/// The command line arguments that correspond to the `PRINTR_*` environment variables that
+/// are set, e.g. `--color=red` for `PRINTR_COLOR=red`. A switch is enabled by `1`, `true`,
+/// `yes` or `on`, disabled by `0`, `false`, `no` or `off` and left alone by an empty value.
+pub fn env_args() -> Result<Vec<String>, String> {
+    env_args_with(|name| env::var(name).ok())
+}
+
+// the arguments of the environment variables, looked up by `var`
+fn env_args_with<F: Fn(&str) -> Option<String>>(var: F) -> Result<Vec<String>, String> {
+    let mut args = vec![];
+    for arg in all_args_and_flags() {
+        let name = match arg.env {
+            Some(name) => name,
+            None => continue,
+        };
+        let value = match var(name) {
+            Some(value) => value,
+            None => continue,
+        };
+        match arg.kind {
+            CustomArgKind::Switch { long, .. } => match value.to_lowercase().as_str() {
+                "1" | "true" | "yes" | "on" => args.push(format!("--{}", long)),
+                "0" | "false" | "no" | "off" => {
+                    args.extend(arg.negation.map(|n| format!("--{}", n)))
+                }
+                "" => {}
+                _ => return Err(format!("expected true or false for {}", name)),
+            },
+            CustomArgKind::Flag { long, .. } if !value.is_empty() => {
+                args.push(format!("--{}={}", long, value));
+            }
+            _ => {}
+        }
+    }
+    Ok(args)
+}
+
+/// The values of the switches and flags of the command line application, by the names of
+/// the arguments. Options from several sources are combined with
+/// [`with_defaults`](#method.with_defaults).
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Options {
+    values: HashMap<&'static str, Setting>,
+}
+
+// the value of a single switch or flag
+#[derive(Debug, PartialEq, Clone)]
+enum Setting {
+    // whether a switch is enabled, `false` if its negation was supplied
+    Switch(bool),
+    // the values of a flag or positional argument
+    Values(Vec<String>),
+}
+
+impl Setting {
+    // whether this setting has any effect, which is what conflicts with other settings
+    fn is_enabled(&self) -> bool {
+        !matches!(self, Setting::Switch(false))
+    }
+}
+
+impl Options {
+    /// The options that were supplied in `matches`.
+    pub fn from_matches(matches: &ArgMatches) -> Self {
+        let mut values = HashMap::new();
+        for arg in all_args_and_flags() {
+            let setting = match arg.kind {
+                CustomArgKind::Switch { .. } if matches.is_present(arg.name) => {
+                    Setting::Switch(true)
+                }
+                CustomArgKind::Switch { .. } => match arg.negation {
+                    Some(negation) if matches.is_present(negation) => Setting::Switch(false),
+                    _ => continue,
+                },
+                _ => match matches.values_of(arg.name) {
+                    Some(args) => Setting::Values(args.map(String::from).collect()),
+                    None => continue,
+                },
+            };
+            values.insert(arg.name, setting);
+        }
+        Self { values }
+    }
+
+    /// The options supplied by `args`, which are validated the same way as the ones on the
+    /// command line, but not combined with them.
+    pub fn parse(args: Vec<String>) -> Result<Self, String> {
+        let args = iter::once("printr".to_string()).chain(args);
+        let matches = app().get_matches_from_safe(args).map_err(|err| {
+            let message = err.message.lines().next().unwrap_or_default();
+            message.trim_start_matches("error: ").to_string()
+        })?;
+        Ok(Self::from_matches(&matches))
+    }
+
+    /// Fill in the options that were not supplied from `defaults`. A default is ignored if
+    /// its option was supplied, even if it was turned off, or if it conflicts with an
+    /// option that is enabled. The values of a flag that can be supplied multiple times
+    /// are combined instead, with the defaults first.
+    pub fn with_defaults(mut self, defaults: Options) -> Self {
+        let args = all_args_and_flags();
+        for (name, setting) in defaults.values {
+            if let Some(supplied) = self.values.get_mut(name) {
+                // the values of a flag that can be supplied multiple times are combined
+                if let (Setting::Values(supplied), Setting::Values(mut values)) =
+                    (supplied, setting)
+                {
+                    if is_multiple(&args, name) {
+                        values.append(supplied);
+                        *supplied = values;
+                    }
+                }
+                continue;
+            }
+            let conflicting = setting.is_enabled()
+                && self
+                    .values
+                    .iter()
+                    .any(|(other, value)| value.is_enabled() && conflict(&args, name, other));
+            if !conflicting {
+                self.values.insert(name, setting);
+            }
+        }
+        self
+    }
+
+    /// Whether the switch or flag called `name` was supplied and, if it is a switch, enabled.
+    pub fn is_present(&self, name: &str) -> bool {
+        self.values.get(name).is_some_and(Setting::is_enabled)
+    }
+
+    /// The value of the flag called `name`, if it was supplied.
+    pub fn value_of(&self, name: &str) -> Option<&str> {
+        self.values_of(name)?.next()
+    }
+
+    /// All the values of the flag called `name`, if it was supplied.
+    pub fn values_of(&self, name: &str) -> Option<impl Iterator<Item = &str>> {
+        match self.values.get(name)? {
+            Setting::Values(values) => Some(values.iter().map(String::as_str)),
+            Setting::Switch(_) => None,
+        }
+    }
+}
+
+// whether the arguments called `first` and `second` cannot be combined
+fn conflict(args: &[CustomArg], first: &str, second: &str) -> bool {
+    args.iter().any(|arg| {
+        (arg.name == first && arg.conflicts.contains(&second))
+            || (arg.name == second && arg.conflicts.contains(&first))
+    })
+}
+
+// whether the flag called `name` can be supplied multiple times
+fn is_multiple(args: &[CustomArg], name: &str) -> bool {
+    args.iter().any(|arg| {
+        arg.name == name && matches!(arg.kind, CustomArgKind::Flag { multiple: true, .. })
+    })
+}
+
+// the value of a flag as it would be supplied on the command line
+// the flags whose values are paths
+const PATHS: &[&str] = &["input-file", "lexicon", "explain-file"];
+
+// the value of a flag of the configuration file in the directory `dir`
+fn flag_value(key: &str, value: &Value, dir: &Path) -> Result<String, String> {
+    let value = scalar(key, value)?;
+    // stdin is not a path
+    match PATHS.contains(&key) && value != STDIN {
+        true => Ok(dir.join(value).to_string_lossy().into_owned()),
+        false => Ok(value),
+    }
+}
+
+fn scalar(key: &str, value: &Value) -> Result<String, String> {
+    match value {
+        Value::String(value) => Ok(value.clone()),
+        Value::Integer(value) => Ok(value.to_string()),
+        Value::Float(value) => Ok(value.to_string()),
+        _ => Err(format!("expected a string or a number for {:?}", key)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_args() {
+        let contents = r#"
+            color-mode = "always"
+            formatting = ["bold", "italic"]
+            neutral-band = 1.5
+            tiers = 5
+            gradient = true
+            words = false
+            lexicon = ["ci-words.tsv", "/usr/share/printr/words.tsv"]
+        "#;
+        assert_eq!(
+            to_args(contents, Path::new("/home/me/.config/printr")),
+            Ok(vec![
+                "--color-mode=always".to_string(),
+                "--formatting=bold".to_string(),
+                "--formatting=italic".to_string(),
+                "--gradient".to_string(),
+                "--lexicon=/home/me/.config/printr/ci-words.tsv".to_string(),
+                "--lexicon=/usr/share/printr/words.tsv".to_string(),
+                "--neutral-band=1.5".to_string(),
+                "--tiers=5".to_string(),
+                "--no-words".to_string(),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_defaults_do_not_override_or_conflict() -> Result<(), String> {
+        let parse = |args: &[&str]| Options::parse(args.iter().map(|s| s.to_string()).collect());
+        assert_eq!(
+            parse(&["--color=red", "--gradient"]),
+            Err("The argument '--color <COLOR>' cannot be used with '--gradient'".to_string())
+        );
+        let defaults = parse(&["--color=red", "--explain", "--tiers=5"])?;
+        let options = parse(&["--plain", "--tiers=3", "--no-explain"])?.with_defaults(defaults);
+        assert!(options.is_present("plain"));
+        // the color conflicts with plain
+        assert_eq!(options.value_of("color"), None);
+        // explain was turned off and the tiers were supplied
+        assert!(!options.is_present("explain"));
+        assert_eq!(options.value_of("tiers"), Some("3"));
+        let defaults = parse(&["--words", "--lang=de", "--formatting=bold"])?;
+        let options = parse(&["--gradient", "--formatting=italic"])?.with_defaults(defaults);
+        assert!(!options.is_present("words"));
+        assert_eq!(options.value_of("lang"), Some("de"));
+        let formats: Vec<&str> = options
+            .values_of("formatting")
+            .into_iter()
+            .flatten()
+            .collect();
+        assert_eq!(formats, vec!["bold", "italic"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_env_args() {
+        let var = |name: &str| match name {
+            "PRINTR_COLOR" => Some("red".to_string()),
+            "PRINTR_FORMATTING" => Some("bold,italic".to_string()),
+            "PRINTR_PLAIN" => Some("yes".to_string()),
+            "PRINTR_ERROR" => Some("0".to_string()),
+            "PRINTR_WORDS" => Some(String::new()),
+            "PRINTR_LANG" => Some(String::new()),
+            _ => None,
+        };
+        assert_eq!(
+            env_args_with(var),
+            Ok(vec![
+                "--plain".to_string(),
+                "--no-error".to_string(),
+                "--color=red".to_string(),
+                "--formatting=bold,italic".to_string(),
+            ])
+        );
+        assert_eq!(
+            env_args_with(|name| (name == "PRINTR_WORDS").then(|| "maybe".to_string())),
+            Err("expected true or false for PRINTR_WORDS".to_string())
+        );
+    }
+
+    #[test]
+    fn test_to_args_rejects_unknown_options() {
+        assert_eq!(
+            to_args("colour = \"red\"", Path::new("")),
+            Err("unknown option \"colour\"".to_string())
+        );
+        assert_eq!(
+            to_args("STRING = \"hello\"", Path::new("")),
+            Err("unknown option \"STRING\"".to_string())
+        );
+        assert_eq!(
+            to_args("plain = \"yes\"", Path::new("")),
+            Err("expected true or false for \"plain\"".to_string())
+        );
+    }
+}
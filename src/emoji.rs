@@ -0,0 +1,192 @@
+//! This module is responsible for recognizing emoji and ASCII emoticons (e.g. `:)`) in text
+//! and scoring them from a built-in table. The scores use the same scale as the AFINN lexicon.
+
+/// The built-in scores of emoji, without any variation selectors or skin tone modifiers.
+const EMOJI: &[(&str, f32)] = &[
+    ("✅", 2.0),
+    ("✔", 2.0),
+    ("☑", 1.0),
+    ("🎉", 3.0),
+    ("🎊", 3.0),
+    ("🥳", 3.0),
+    ("🏆", 3.0),
+    ("🚀", 2.0),
+    ("⭐", 2.0),
+    ("🌟", 2.0),
+    ("✨", 1.0),
+    ("💯", 2.0),
+    ("👍", 2.0),
+    ("👏", 2.0),
+    ("🙌", 2.0),
+    ("💪", 2.0),
+    ("❤", 3.0),
+    ("💚", 3.0),
+    ("😀", 2.0),
+    ("😃", 2.0),
+    ("😄", 2.0),
+    ("😁", 2.0),
+    ("😂", 2.0),
+    ("🤣", 2.0),
+    ("😊", 2.0),
+    ("🙂", 1.0),
+    ("😉", 1.0),
+    ("😍", 3.0),
+    ("😘", 2.0),
+    ("😎", 2.0),
+    ("❌", -2.0),
+    ("✖", -2.0),
+    ("❎", -1.0),
+    ("⛔", -2.0),
+    ("🚫", -2.0),
+    ("⚠", -1.0),
+    ("🚨", -2.0),
+    ("🐛", -1.0),
+    ("💥", -1.0),
+    ("💀", -2.0),
+    ("👎", -2.0),
+    ("💔", -3.0),
+    ("🙁", -1.0),
+    ("☹", -2.0),
+    ("😕", -1.0),
+    ("😞", -2.0),
+    ("😔", -2.0),
+    ("😟", -2.0),
+    ("😢", -2.0),
+    ("😭", -2.0),
+    ("😩", -2.0),
+    ("😫", -2.0),
+    ("😨", -2.0),
+    ("😰", -2.0),
+    ("😱", -2.0),
+    ("😤", -2.0),
+    ("😠", -3.0),
+    ("😡", -3.0),
+    ("🤬", -4.0),
+];
+
+/// The built-in scores of ASCII emoticons.
+const EMOTICONS: &[(&str, f32)] = &[
+    (":)", 2.0),
+    (":-)", 2.0),
+    ("(:", 2.0),
+    (":]", 2.0),
+    (";)", 1.0),
+    (";-)", 1.0),
+    (":D", 3.0),
+    (":-D", 3.0),
+    (":P", 1.0),
+    (":-P", 1.0),
+    (":*", 2.0),
+    ("^_^", 2.0),
+    ("<3", 3.0),
+    ("</3", -3.0),
+    (":(", -2.0),
+    (":-(", -2.0),
+    ("):", -2.0),
+    (":[", -2.0),
+    (":'(", -2.0),
+    (">:(", -3.0),
+    (":/", -1.0),
+    (":-/", -1.0),
+    (":|", -1.0),
+];
+
+/// The score of an emoji or emoticon, if it is in the built-in table.
+pub fn score(token: &str) -> Option<f32> {
+    let stripped: String = token.chars().filter(|&c| !is_modifier(c)).collect();
+    let lookup = |token: &str| {
+        EMOJI
+            .iter()
+            .chain(EMOTICONS)
+            .find(|(entry, _)| *entry == token)
+            .map(|&(_, score)| score)
+    };
+    // a sequence of emoji joined together is scored by the first emoji if it is not known
+    lookup(&stripped).or_else(|| lookup(&stripped.chars().next()?.to_string()))
+}
+
+/// Whether `c` starts an emoji.
+pub fn is_emoji(c: char) -> bool {
+    matches!(c as u32, 0x2300..=0x23FF | 0x2600..=0x27BF | 0x2B00..=0x2BFF | 0x1F000..=0x1FAFF)
+}
+
+/// The byte index at which the emoji starting at `start` ends. This includes any variation
+/// selectors and skin tone modifiers as well as emoji joined to it with zero width joiners.
+pub fn emoji_end(string: &str, start: usize) -> usize {
+    let mut chars = string[start..].char_indices().skip(1).peekable();
+    while let Some((index, c)) = chars.next() {
+        if is_modifier(c) {
+            continue;
+        }
+        match (c, chars.peek()) {
+            (ZERO_WIDTH_JOINER, Some(&(_, next))) if is_emoji(next) => {
+                chars.next();
+            }
+            _ => return start + index,
+        }
+    }
+    string.len()
+}
+
+/// The length of the emoticon at `start`, if there is one. An emoticon must be preceded by
+/// whitespace and followed by whitespace or punctuation, so that e.g. the `:/` in a URL is
+/// not mistaken for one.
+pub fn emoticon_len(string: &str, start: usize) -> Option<usize> {
+    let preceded_by_whitespace = string[..start]
+        .chars()
+        .next_back()
+        .is_none_or(char::is_whitespace);
+    if !preceded_by_whitespace {
+        return None;
+    }
+    let rest = &string[start..];
+    EMOTICONS
+        .iter()
+        .map(|&(emoticon, _)| emoticon)
+        .filter(|emoticon| rest.starts_with(emoticon))
+        .filter(|emoticon| {
+            rest[emoticon.len()..]
+                .chars()
+                .next()
+                .is_none_or(|c| c.is_whitespace() || ".,!?".contains(c))
+        })
+        .map(str::len)
+        .max()
+}
+
+const ZERO_WIDTH_JOINER: char = '\u{200d}';
+
+// whether `c` modifies the emoji preceding it
+fn is_modifier(c: char) -> bool {
+    matches!(c as u32, 0xFE0E | 0xFE0F | 0x20E3 | 0x1F3FB..=0x1F3FF)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_score_ignores_modifiers() {
+        assert_eq!(score("✅"), Some(2.0));
+        assert_eq!(score("✔\u{fe0f}"), Some(2.0));
+        assert_eq!(score("👍🏽"), Some(2.0));
+        assert_eq!(score(":("), Some(-2.0));
+        assert_eq!(score("🦀"), None);
+    }
+
+    #[test]
+    fn test_emoji_end_includes_joined_emoji() {
+        let string = "👍🏽 and 🧑\u{200d}🚀!";
+        assert_eq!(emoji_end(string, 0), "👍🏽".len());
+        let start = string.find('🧑').unwrap();
+        assert_eq!(emoji_end(string, start), string.len() - 1);
+    }
+
+    #[test]
+    fn test_emoticon_len_requires_boundaries() {
+        assert_eq!(emoticon_len("nice :-) indeed", 5), Some(3));
+        assert_eq!(emoticon_len(">:(.", 0), Some(3));
+        assert_eq!(emoticon_len("https://example.com", 5), None);
+        assert_eq!(emoticon_len("nice:)", 4), None);
+    }
+}
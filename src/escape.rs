@@ -0,0 +1,128 @@
+//! This module is responsible for interpreting the backslash escapes that are recognized
+//! when the `-e` switch is supplied. The behavior mirrors that of `echo -e`.
+
+/// The result of interpreting all the escape sequences in a string.
+#[derive(Debug, PartialEq)]
+pub struct Interpreted {
+    /// The string with all the escape sequences replaced.
+    pub string: String,
+    /// Whether a `\c` was encountered, in which case no further output should be produced.
+    pub stopped: bool,
+}
+
+/// Replace all the escape sequences in `input` with the bytes they represent.
+///
+/// The octal (`\0NNN`) and hexadecimal (`\xHH`) escapes produce raw bytes, so consecutive
+/// escapes can be used to spell out multi-byte UTF-8 characters. Any resulting byte sequence
+/// that is not valid UTF-8 is replaced with `U+FFFD`. Unrecognized escapes are left as is.
+pub fn interpret(input: &str) -> Interpreted {
+    let bytes = input.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut stopped = false;
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] != b'\\' || i + 1 == bytes.len() {
+            out.push(bytes[i]);
+            i += 1;
+            continue;
+        }
+        i += 1;
+        let escape = bytes[i];
+        i += 1;
+        match escape {
+            b'\\' => out.push(b'\\'),
+            b'a' => out.push(0x07),
+            b'b' => out.push(0x08),
+            b'c' => {
+                stopped = true;
+                break;
+            }
+            b'e' => out.push(0x1b),
+            b'f' => out.push(0x0c),
+            b'n' => out.push(b'\n'),
+            b'r' => out.push(b'\r'),
+            b't' => out.push(b'\t'),
+            b'v' => out.push(0x0b),
+            b'0' => {
+                let (value, len) = parse_digits(&bytes[i..], 8, 3);
+                out.push(value as u8);
+                i += len;
+            }
+            b'x' => {
+                let (value, len) = parse_digits(&bytes[i..], 16, 2);
+                if len == 0 {
+                    out.extend_from_slice(b"\\x");
+                } else {
+                    out.push(value as u8);
+                    i += len;
+                }
+            }
+            other => {
+                out.push(b'\\');
+                out.push(other);
+            }
+        }
+    }
+    Interpreted {
+        string: String::from_utf8_lossy(&out).into_owned(),
+        stopped,
+    }
+}
+
+/// Parse at most `max` digits in the given `radix` from the start of `bytes`, returning the
+/// value and the number of digits consumed.
+fn parse_digits(bytes: &[u8], radix: u32, max: usize) -> (u32, usize) {
+    let mut value = 0;
+    let mut len = 0;
+    for &b in bytes.iter().take(max) {
+        match (b as char).to_digit(radix) {
+            Some(digit) => {
+                value = value * radix + digit;
+                len += 1;
+            }
+            None => break,
+        }
+    }
+    (value, len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn interpreted(input: &str) -> String {
+        interpret(input).string
+    }
+
+    #[test]
+    fn test_simple_escapes_are_replaced() {
+        assert_eq!(interpreted(r"a\tb\nc"), "a\tb\nc");
+        assert_eq!(interpreted(r"\a\b\e\f\r\v"), "\x07\x08\x1b\x0c\r\x0b");
+        assert_eq!(interpreted(r"back\\slash"), r"back\slash");
+    }
+
+    #[test]
+    fn test_numeric_escapes_produce_bytes() {
+        assert_eq!(interpreted(r"\0101\x42"), "AB");
+        assert_eq!(interpreted(r"\0"), "\0");
+        assert_eq!(interpreted(r"\xe2\x9c\x93"), "✓");
+        assert_eq!(interpreted(r"\x4g"), "\x04g");
+        assert_eq!(interpreted(r"\xff"), "\u{fffd}");
+    }
+
+    #[test]
+    fn test_unknown_escapes_are_left_as_is() {
+        assert_eq!(interpreted(r"\q \xzz trailing\"), r"\q \xzz trailing\");
+    }
+
+    #[test]
+    fn test_stop_escape_discards_the_rest() {
+        assert_eq!(
+            interpret(r"first\cSecond\n"),
+            Interpreted {
+                string: "first".to_string(),
+                stopped: true,
+            }
+        );
+    }
+}
@@ -0,0 +1,259 @@
+//! This module adjusts the scores of the tokens found by an analyzer, in the spirit of
+//! [VADER](https://github.com/cjhutto/vaderSentiment), so that the context a word appears in
+//! is taken into account. The following rules are applied to every token with a score:
+//!
+//! - A negation (e.g. `not`, `never`, `isn't`) in the three preceding words flips the score.
+//! - An intensifier (e.g. `very`) in the three preceding words increases the score, a
+//!   dampener (e.g. `slightly`) decreases it.
+//! - A word in ALL-CAPS is emphasized, unless the whole text is in capitals.
+//! - Every exclamation mark (up to four) emphasizes all the scores of the text.
+//! - The scores before a `but` are halved, while the ones after it are increased.
+//!
+//! Each supported [`Language`](../language/enum.Language.html) has its own negations,
+//! intensifiers and so on.
+use crate::{analyzer::Token, language::Language};
+
+/// The factor a score is multiplied by when it is negated.
+const NEGATION: f32 = -0.75;
+/// The factor a score is multiplied by for each preceding intensifier.
+const INTENSIFIER: f32 = 1.25;
+/// The factor a score is multiplied by for each preceding dampener.
+const DAMPENER: f32 = 0.75;
+/// The factor the score of a word in ALL-CAPS is multiplied by.
+const CAPS: f32 = 1.5;
+/// The emphasis each exclamation mark adds to the scores.
+const EXCLAMATION: f32 = 0.125;
+/// The maximum number of exclamation marks that add emphasis.
+const MAX_EXCLAMATIONS: usize = 4;
+/// The factors the scores before and after a `but` are multiplied by.
+const BEFORE_BUT: f32 = 0.5;
+const AFTER_BUT: f32 = 1.5;
+/// The number of preceding words that are checked for negations and intensifiers.
+const SCOPE: usize = 3;
+
+/// The words the heuristics look for in a language.
+struct Words {
+    negations: &'static [&'static str],
+    intensifiers: &'static [&'static str],
+    dampeners: &'static [&'static str],
+    // the word that starts a clause which shifts the weight, like `but`
+    contrast: &'static str,
+}
+
+const ENGLISH: Words = Words {
+    negations: &[
+        "aint", "cannot", "cant", "dont", "isnt", "neither", "never", "no", "nobody", "none",
+        "nor", "not", "nothing", "nowhere", "wont", "without",
+    ],
+    intensifiers: &[
+        "absolutely",
+        "completely",
+        "deeply",
+        "enormously",
+        "especially",
+        "exceptionally",
+        "extremely",
+        "greatly",
+        "highly",
+        "hugely",
+        "incredibly",
+        "particularly",
+        "really",
+        "remarkably",
+        "so",
+        "thoroughly",
+        "totally",
+        "tremendously",
+        "truly",
+        "utterly",
+        "very",
+    ],
+    dampeners: &[
+        "almost",
+        "barely",
+        "hardly",
+        "marginally",
+        "partly",
+        "scarcely",
+        "slightly",
+        "somewhat",
+    ],
+    contrast: "but",
+};
+
+const GERMAN: Words = Words {
+    negations: &[
+        "kein", "keine", "keinem", "keinen", "keiner", "keines", "nicht", "nichts", "nie",
+        "niemals", "ohne", "weder",
+    ],
+    intensifiers: &[
+        "absolut",
+        "äußerst",
+        "besonders",
+        "echt",
+        "extrem",
+        "sehr",
+        "so",
+        "total",
+        "völlig",
+        "wirklich",
+    ],
+    dampeners: &["bisschen", "etwas", "fast", "kaum", "leicht"],
+    contrast: "aber",
+};
+
+const SPANISH: Words = Words {
+    negations: &[
+        "jamás", "nada", "ni", "ninguna", "ninguno", "ningún", "no", "nunca", "sin", "tampoco",
+    ],
+    intensifiers: &[
+        "extremadamente",
+        "muy",
+        "realmente",
+        "súper",
+        "tan",
+        "totalmente",
+    ],
+    dampeners: &["algo", "apenas", "casi", "ligeramente", "poco"],
+    contrast: "pero",
+};
+
+const FRENCH: Words = Words {
+    negations: &[
+        "aucun", "aucune", "jamais", "ni", "pas", "personne", "rien", "sans",
+    ],
+    intensifiers: &[
+        "complètement",
+        "extrêmement",
+        "tellement",
+        "totalement",
+        "très",
+        "trop",
+        "vraiment",
+    ],
+    dampeners: &["guère", "légèrement", "peu", "presque"],
+    contrast: "mais",
+};
+
+/// Adjust the scores of `tokens`, which were found in `text`, by the rules described in the
+/// [module documentation](index.html). The words that are looked for depend on the
+/// `language` of the text.
+pub fn apply(text: &str, tokens: &mut [Token], language: Language) {
+    let rules = match language.resolve(text) {
+        Language::Auto | Language::English => &ENGLISH,
+        Language::German => &GERMAN,
+        Language::Spanish => &SPANISH,
+        Language::French => &FRENCH,
+    };
+    let words: Vec<String> = tokens.iter().map(|t| t.text.to_lowercase()).collect();
+    let mixed_case = tokens
+        .iter()
+        .any(|t| t.text.chars().any(char::is_lowercase));
+    let exclamations = text.matches('!').count().min(MAX_EXCLAMATIONS);
+    let emphasis = 1.0 + EXCLAMATION * exclamations as f32;
+    let but = words.iter().rposition(|word| word == rules.contrast);
+    for (index, token) in tokens.iter_mut().enumerate() {
+        if token.score == 0.0 {
+            continue;
+        }
+        let mut score = token.score;
+        if mixed_case && is_shouted(&token.text) {
+            score *= CAPS;
+        }
+        let preceding = &words[index.saturating_sub(SCOPE)..index];
+        for word in preceding {
+            if rules.intensifiers.contains(&word.as_str()) {
+                score *= INTENSIFIER;
+            } else if rules.dampeners.contains(&word.as_str()) {
+                score *= DAMPENER;
+            }
+        }
+        if preceding.iter().any(|word| is_negation(rules, word)) {
+            score *= NEGATION;
+        }
+        match but {
+            Some(but) if index < but => score *= BEFORE_BUT,
+            Some(but) if index > but => score *= AFTER_BUT,
+            _ => {}
+        }
+        token.score = score * emphasis;
+    }
+}
+
+// whether `word` (in lowercase) negates the words following it
+fn is_negation(rules: &Words, word: &str) -> bool {
+    rules.negations.contains(&word) || word.ends_with("n't")
+}
+
+// whether `word` has at least two letters, all of which are in capitals
+fn is_shouted(word: &str) -> bool {
+    word.chars().filter(|c| c.is_alphabetic()).count() > 1
+        && !word.chars().any(|c| c.is_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // apply the heuristics to `text`, where every word in `scored` has the given score
+    fn scores(text: &str, scored: &[(&str, f32)]) -> Vec<f32> {
+        let mut tokens: Vec<Token> = crate::analyzer::tokenize(text)
+            .into_iter()
+            .map(|word| {
+                let score = scored
+                    .iter()
+                    .find(|(w, _)| w.eq_ignore_ascii_case(word))
+                    .map_or(0.0, |&(_, score)| score);
+                Token::new(word, score)
+            })
+            .collect();
+        apply(text, &mut tokens, Language::Auto);
+        tokens.into_iter().map(|t| t.score).collect()
+    }
+
+    #[test]
+    fn test_negation_flips_the_score() {
+        assert_eq!(scores("not good", &[("good", 3.0)]), vec![0.0, -2.25]);
+        assert_eq!(
+            scores("it isn't very good", &[("good", 4.0)]),
+            vec![0.0, 0.0, 0.0, -3.75]
+        );
+        assert_eq!(
+            scores("no, it is not bad", &[("bad", -2.0)]),
+            vec![0.0, 0.0, 0.0, 0.0, 1.5]
+        );
+    }
+
+    #[test]
+    fn test_intensifiers_and_dampeners() {
+        assert_eq!(scores("very bad", &[("bad", -4.0)]), vec![0.0, -5.0]);
+        assert_eq!(scores("slightly bad", &[("bad", -4.0)]), vec![0.0, -3.0]);
+    }
+
+    #[test]
+    fn test_caps_and_exclamations_add_emphasis() {
+        assert_eq!(scores("it is BAD", &[("bad", -2.0)]), vec![0.0, 0.0, -3.0]);
+        assert_eq!(scores("VERY BAD!!!", &[("bad", -2.0)]), vec![0.0, -3.4375]);
+        assert_eq!(scores("bad!!!!!!", &[("bad", -2.0)]), vec![-3.0]);
+    }
+
+    #[test]
+    fn test_words_depend_on_the_language() {
+        assert_eq!(
+            scores("das ist nicht gut", &[("gut", 2.0)]),
+            vec![0.0, 0.0, 0.0, -1.5]
+        );
+        assert_eq!(
+            scores("ce n'est pas bon", &[("bon", 2.0)]),
+            vec![0.0, 0.0, 0.0, -1.5]
+        );
+    }
+
+    #[test]
+    fn test_but_shifts_the_weight() {
+        assert_eq!(
+            scores("good, but bad", &[("good", 2.0), ("bad", -2.0)]),
+            vec![1.0, 0.0, -3.0]
+        );
+    }
+}
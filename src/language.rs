@@ -0,0 +1,169 @@
+//! This module is responsible for the languages supported by the sentiment analysis and for
+//! detecting the language of a piece of text.
+use crate::lexicon::Lexicon;
+use std::{fmt, str::FromStr, sync::OnceLock};
+
+/// The languages whose lexicons are bundled with `printr`.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum Language {
+    /// Detect the language of every text before analysing it.
+    Auto,
+    /// English, scored by the AFINN lexicon.
+    #[default]
+    English,
+    /// German, scored by the bundled `lexicons/de.tsv`.
+    German,
+    /// Spanish, scored by the bundled `lexicons/es.tsv`.
+    Spanish,
+    /// French, scored by the bundled `lexicons/fr.tsv`.
+    French,
+}
+
+const GERMAN_LEXICON: &str = include_str!("../lexicons/de.tsv");
+const SPANISH_LEXICON: &str = include_str!("../lexicons/es.tsv");
+const FRENCH_LEXICON: &str = include_str!("../lexicons/fr.tsv");
+
+// common words which are used to detect the language of a text
+const ENGLISH_WORDS: &[&str] = &[
+    "a", "and", "are", "be", "but", "for", "from", "has", "have", "i", "in", "is", "it", "not",
+    "of", "on", "that", "the", "this", "to", "was", "we", "were", "will", "with", "you",
+];
+const GERMAN_WORDS: &[&str] = &[
+    "auch", "auf", "aber", "das", "dem", "den", "der", "des", "die", "ein", "eine", "einen", "es",
+    "für", "ich", "ist", "kein", "mit", "nicht", "noch", "sind", "und", "von", "war", "wir",
+    "wurde", "zu",
+];
+const SPANISH_WORDS: &[&str] = &[
+    "al", "como", "con", "de", "del", "el", "en", "es", "está", "fue", "hay", "la", "las", "lo",
+    "los", "muy", "no", "para", "pero", "por", "que", "se", "son", "su", "un", "una", "y",
+];
+const FRENCH_WORDS: &[&str] = &[
+    "au", "avec", "ce", "dans", "de", "des", "du", "est", "et", "été", "il", "je", "la", "le",
+    "les", "mais", "ne", "nous", "pas", "pour", "que", "sont", "sur", "très", "un", "une",
+];
+
+impl Language {
+    /// Detect the language of `text` by counting the common words of each language in it.
+    /// Characters that are specific to a language (e.g. `ß` or `ñ`) count as well. When no
+    /// language stands out, the text is assumed to be in English.
+    pub fn detect(text: &str) -> Language {
+        let text = text.to_lowercase();
+        let words = crate::analyzer::tokenize(&text);
+        let count = |common: &[&str], characters: &str| {
+            let words = words.iter().filter(|word| common.contains(word)).count();
+            words + text.chars().filter(|&c| characters.contains(c)).count()
+        };
+        let candidates = [
+            (Language::English, count(ENGLISH_WORDS, "")),
+            (Language::German, count(GERMAN_WORDS, "äöüß")),
+            (Language::Spanish, count(SPANISH_WORDS, "ñ¿¡")),
+            (Language::French, count(FRENCH_WORDS, "çèêœ")),
+        ];
+        let mut detected = (Language::English, 0);
+        for candidate in &candidates {
+            if candidate.1 > detected.1 {
+                detected = *candidate;
+            }
+        }
+        detected.0
+    }
+
+    /// The language that `text` should be analysed in, which is this language unless it is
+    /// `Auto`, in which case it is detected from `text`.
+    pub fn resolve(self, text: &str) -> Language {
+        match self {
+            Language::Auto => Language::detect(text),
+            language => language,
+        }
+    }
+
+    /// The bundled lexicon of this language. English (and `Auto`) has none, since it is
+    /// scored by the AFINN lexicon.
+    pub(crate) fn lexicon(self) -> Option<&'static Lexicon> {
+        static GERMAN: OnceLock<Lexicon> = OnceLock::new();
+        static SPANISH: OnceLock<Lexicon> = OnceLock::new();
+        static FRENCH: OnceLock<Lexicon> = OnceLock::new();
+        let (cell, contents) = match self {
+            Language::Auto | Language::English => return None,
+            Language::German => (&GERMAN, GERMAN_LEXICON),
+            Language::Spanish => (&SPANISH, SPANISH_LEXICON),
+            Language::French => (&FRENCH, FRENCH_LEXICON),
+        };
+        Some(
+            cell.get_or_init(|| {
+                Lexicon::parse_tsv(contents).expect("the bundled lexicons are valid")
+            }),
+        )
+    }
+}
+
+impl FromStr for Language {
+    type Err = String;
+
+    /// Parse a language from its ISO 639-1 code (e.g. `de`) or `auto`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "auto" => Ok(Language::Auto),
+            "en" => Ok(Language::English),
+            "de" => Ok(Language::German),
+            "es" => Ok(Language::Spanish),
+            "fr" => Ok(Language::French),
+            _ => Err(format!(
+                "unknown language {:?}, expected auto, en, de, es or fr",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let code = match self {
+            Language::Auto => "auto",
+            Language::English => "en",
+            Language::German => "de",
+            Language::Spanish => "es",
+            Language::French => "fr",
+        };
+        f.write_str(code)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect() {
+        assert_eq!(Language::detect("The build is green"), Language::English);
+        assert_eq!(
+            Language::detect("Der Build ist fehlgeschlagen"),
+            Language::German
+        );
+        assert_eq!(Language::detect("Grüße"), Language::German);
+        assert_eq!(
+            Language::detect("El despliegue fue un éxito"),
+            Language::Spanish
+        );
+        assert_eq!(
+            Language::detect("Le déploiement a échoué, très mauvais"),
+            Language::French
+        );
+        assert_eq!(Language::detect("12345"), Language::English);
+    }
+
+    #[test]
+    fn test_languages_are_parsed() {
+        assert_eq!("DE".parse(), Ok(Language::German));
+        assert_eq!(Language::Auto.to_string().parse(), Ok(Language::Auto));
+        assert!("nl".parse::<Language>().is_err());
+    }
+
+    #[test]
+    fn test_bundled_lexicons_are_valid() {
+        assert_eq!(Language::English.lexicon(), None);
+        assert_eq!(Language::German.lexicon().unwrap().get("Gut"), Some(3.0));
+        assert_eq!(Language::Spanish.lexicon().unwrap().get("malo"), Some(-3.0));
+        assert_eq!(Language::French.lexicon().unwrap().get("échec"), Some(-3.0));
+    }
+}
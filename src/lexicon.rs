@@ -0,0 +1,144 @@
+//! This module is responsible for loading user supplied lexicons, which add to or override
+//! the scores of the words in the default AFINN lexicon.
+use std::{
+    collections::HashMap,
+    fs::read_to_string,
+    io::{self, ErrorKind},
+    path::Path,
+};
+
+/// A mapping of words (or phrases) to their sentiment scores.
+///
+/// All entries are stored in lowercase and phrases have their words separated by a single
+/// space, so lookups are case insensitive.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Lexicon {
+    entries: HashMap<String, f32>,
+    // the number of words in the longest phrase of this lexicon
+    longest_phrase: usize,
+}
+
+impl Lexicon {
+    /// Create an empty lexicon.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load a lexicon from a file. Files whose content starts with a `{` are parsed as
+    /// JSON, all others as TSV. Refer to [`parse_json`](#method.parse_json) and
+    /// [`parse_tsv`](#method.parse_tsv) for the formats.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let contents = read_to_string(path)?;
+        let lexicon = if contents.trim_start().starts_with('{') {
+            Self::parse_json(&contents)
+        } else {
+            Self::parse_tsv(&contents)
+        };
+        lexicon.map_err(|err| io::Error::new(ErrorKind::InvalidData, err))
+    }
+
+    /// Parse a lexicon in the same format as the AFINN word lists, that is, a word (or
+    /// phrase) and its score separated by a tab on each line. Empty lines and lines starting
+    /// with a `#` are ignored.
+    pub fn parse_tsv(contents: &str) -> Result<Self, String> {
+        let mut lexicon = Self::new();
+        for (number, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (word, score) = match line.rfind('\t') {
+                Some(index) => (&line[..index], line[index + 1..].trim()),
+                None => {
+                    return Err(format!(
+                        "line {}: expected a tab separated score",
+                        number + 1
+                    ))
+                }
+            };
+            let score = score
+                .parse()
+                .map_err(|_| format!("line {}: invalid score {:?}", number + 1, score))?;
+            lexicon.insert(word, score);
+        }
+        Ok(lexicon)
+    }
+
+    /// Parse a lexicon from a JSON object whose keys are the words (or phrases) and whose
+    /// values are their scores, e.g. `{"flaky": -2, "green build": 3}`.
+    pub fn parse_json(contents: &str) -> Result<Self, String> {
+        let entries: HashMap<String, f32> =
+            serde_json::from_str(contents).map_err(|err| err.to_string())?;
+        let mut lexicon = Self::new();
+        for (word, score) in entries {
+            lexicon.insert(&word, score);
+        }
+        Ok(lexicon)
+    }
+
+    /// Add a word (or phrase) to this lexicon, overriding its previous score, if any.
+    pub fn insert(&mut self, word: &str, score: f32) {
+        let word = normalize(word);
+        self.longest_phrase = self.longest_phrase.max(word.split(' ').count());
+        self.entries.insert(word, score);
+    }
+
+    /// Add all the entries of `other` to this lexicon, overriding the existing scores.
+    pub fn extend(&mut self, other: Lexicon) {
+        self.longest_phrase = self.longest_phrase.max(other.longest_phrase);
+        self.entries.extend(other.entries);
+    }
+
+    /// Get the score of a word (or phrase).
+    pub fn get(&self, word: &str) -> Option<f32> {
+        self.entries.get(&normalize(word)).copied()
+    }
+
+    /// The number of words in the longest phrase of this lexicon.
+    pub fn longest_phrase(&self) -> usize {
+        self.longest_phrase
+    }
+
+    /// Whether this lexicon has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+// lowercase `word` and collapse all whitespace into single spaces
+fn normalize(word: &str) -> String {
+    word.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{error::Error, io::Write};
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_parse_tsv() -> Result<(), Box<dyn Error>> {
+        let lexicon = Lexicon::parse_tsv("# CI words\nflaky\t-2\n\nGreen  Build\t3\n")?;
+        assert_eq!(lexicon.get("Flaky"), Some(-2.0));
+        assert_eq!(lexicon.get("green build"), Some(3.0));
+        assert_eq!(lexicon.longest_phrase(), 2);
+        assert_eq!(
+            Lexicon::parse_tsv("flaky -2"),
+            Err("line 1: expected a tab separated score".to_string())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_file_detects_json() -> Result<(), Box<dyn Error>> {
+        let mut file = NamedTempFile::new()?;
+        write!(file, r#"{{"regression": -3, "flaky": -1.5}}"#)?;
+        let lexicon = Lexicon::from_file(file.path())?;
+        assert_eq!(lexicon.get("regression"), Some(-3.0));
+        assert_eq!(lexicon.get("flaky"), Some(-1.5));
+        Ok(())
+    }
+}
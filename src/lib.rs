@@ -1,179 +1,572 @@
-use ansi_term::Colour::{Blue, Cyan, Green, Red, Yellow};
+use analyzer::{Afinn, Sentiment, SentimentAnalyzer, Thresholds};
+use ansi_term::Colour;
 use ansi_term::Style;
-use std::{f32::EPSILON, fs::read_to_string, process};
+use serde_json::json;
+use std::{
+    error, fmt, fs,
+    io::{self, BufRead, Read, Write},
+    str::FromStr,
+};
+use theme::Icons;
+pub mod analyzer;
 pub mod app;
+pub mod config;
+mod emoji;
+mod escape;
+pub mod heuristics;
+pub mod language;
+pub mod lexicon;
+#[doc(hidden)]
+pub mod macros;
+pub mod theme;
+
+/// The file name which, when supplied as the input file, makes `printr` read from stdin
+/// instead.
+pub const STDIN: &str = "-";
+
+/// The errors that can occur while creating or running a `printr` object.
+#[derive(Debug)]
+pub enum Error {
+    /// The input could not be read or the output could not be written.
+    Io(io::Error),
+    /// Options that contradict each other were supplied.
+    InvalidOption(String),
+    /// The input, read from the file or stdin named by this, is not valid UTF-8.
+    Encoding(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io(err) => write!(f, "{}", err),
+            Error::InvalidOption(msg) => write!(f, "invalid options: {}", msg),
+            Error::Encoding(input) => write!(f, "{} is not valid UTF-8", input),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
 
 /// This function takes a `printr` object and runs it through all the associated methods so
 /// that all the arguments, flags and switches are accounted for.
-pub fn run(printr: &mut Printr) {
-    printr.run_all_handles()
-}
-
-#[derive(Debug, PartialEq)]
-struct Config {
-    // if `-E` is supplied, this will be `false`
-    interpretations: bool,
-    // if `-n` is supplied, this will be `true`
-    newline: bool,
-    // if `-s` is supplied, this will become `true`
-    spaces: bool,
-    // the color of the output, will be automatically guessed from the context if not supplied
-    // can be set to `None` for plain output
-    // the possible values are red (-1), blue (0), green (1), yellow, cyan, None
-    color: Option<Color>,
-    // the formatting to be applied to the output string
-    format: Option<Format>,
-    // whether the output should be completely plain
-    plain: bool,
-}
-
-impl Config {
-    fn new(
-        interpretations: bool,
-        newline: bool,
-        spaces: bool,
-        plain: bool,
-        color: Option<Color>,
-        format: Option<Format>,
-    ) -> Self {
+///
+/// **NOTE:** None of these methods can fail at the moment, so this always returns `Ok`. The
+/// `Result` is kept so that they can report errors without breaking this function.
+pub fn run<A: SentimentAnalyzer>(printr: &mut Printr<A>) -> Result<(), Error> {
+    printr.run_all_handles();
+    Ok(())
+}
+
+/// This function takes a `printr` object and runs it over every line read from `reader`, so
+/// that the sentiment (and hence the color) of each line is determined separately. Every line
+/// is written to `writer` as soon as it has been processed.
+///
+/// The `STRING` of the `printr` object is ignored, all the other options apply to each line.
+/// The same object (and hence the same analyzer) is reused for every line, only its input and
+/// the state derived from it are reset.
+/// If `explain` is supplied, the [`explain`](struct.Printr.html#method.explain) report of
+/// every line is written to it as well, one report per line.
+pub fn run_lines<A: SentimentAnalyzer, R: BufRead, W: Write>(
+    printr: &mut Printr<A>,
+    reader: R,
+    writer: W,
+    explain: Option<&mut dyn Write>,
+) -> Result<(), Error> {
+    // the color and background are replaced by the guessed ones while a line is processed, so
+    // the supplied ones are restored afterwards
+    let color = printr.config.color.clone();
+    let background = printr.config.background.clone();
+    let result = process_lines(printr, reader, writer, explain, &color, &background);
+    printr.config.color = color;
+    printr.config.background = background;
+    result
+}
+
+// `run_lines`, with every line starting over from `color` and `background`
+fn process_lines<A: SentimentAnalyzer, R: BufRead, W: Write>(
+    printr: &mut Printr<A>,
+    reader: R,
+    mut writer: W,
+    mut explain: Option<&mut dyn Write>,
+    color: &Option<Color>,
+    background: &Option<Background>,
+) -> Result<(), Error> {
+    for line in reader.lines() {
+        let line = line.map_err(|err| match err.kind() {
+            io::ErrorKind::InvalidData => Error::Encoding("the input".to_string()),
+            _ => Error::Io(err),
+        })?;
+        printr.reset(vec![line], color.clone(), background.clone());
+        printr.run_all_handles();
+        if let Some(explain) = explain.as_mut() {
+            writeln!(explain, "{}", printr.explain())?;
+        }
+        printr.write_to(&mut writer)?;
+        writer.flush()?;
+        if printr.stopped {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// The options that determine how the input is printed, which correspond to the switches and
+/// flags of the command line application. Refer to
+/// [`PrintrBuilder::config`](struct.PrintrBuilder.html#method.config) to use it.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default, rename_all = "kebab-case")
+)]
+pub struct Config {
+    /// Whether backslash escapes in the input are interpreted, like `-e` does.
+    pub interpretations: bool,
+    /// Whether a newline is put at the end of the output, which `-n` turns off.
+    pub newline: bool,
+    /// Whether the strings of the input are separated by spaces, which `-s` turns off.
+    pub spaces: bool,
+    /// The color of the output, which is guessed from the sentiment of the input if this is
+    /// `None`.
+    pub color: Option<Color>,
+    /// The formats applied to the output.
+    pub format: Vec<Format>,
+    /// Whether the output is left completely plain.
+    pub plain: bool,
+    /// Whether every word of the output is colored by its own sentiment, like `-w` does.
+    pub words: bool,
+    /// The thresholds within which the sentiment is neutral.
+    pub thresholds: Thresholds,
+    /// The colors that the guessed sentiment is mapped to.
+    pub colors: ColorMap,
+    /// Whether the color is interpolated from the net score of the sentiment, like
+    /// `--gradient` does.
+    pub gradient: bool,
+    /// The background of the output, like `-b` sets.
+    pub background: Option<Background>,
+    /// Whether any colors or formats are applied to the output at all, see
+    /// [`ColorMode`](enum.ColorMode.html).
+    pub styled: bool,
+    /// The text put before the output, see
+    /// [`PrintrBuilder::prefix`](struct.PrintrBuilder.html#method.prefix).
+    pub prefix: String,
+    /// The icons that can be used in the `prefix`.
+    pub icons: Icons,
+}
+
+impl Default for Config {
+    fn default() -> Self {
         Self {
-            interpretations,
-            newline,
-            spaces,
-            plain,
-            color,
-            format,
+            interpretations: false,
+            newline: true,
+            spaces: true,
+            color: None,
+            format: vec![],
+            plain: false,
+            words: false,
+            thresholds: Thresholds::default(),
+            colors: ColorMap::default(),
+            gradient: false,
+            background: None,
+            styled: true,
+            prefix: String::new(),
+            icons: Icons::default(),
         }
     }
 }
 
 /// The main struct that is responsible for generating the output string that will be
 /// displayed to the end user.
-#[derive(Debug, PartialEq)]
-pub struct Printr {
+///
+/// It is created with a [`PrintrBuilder`](struct.PrintrBuilder.html), refer to
+/// [`Printr::builder`](struct.Printr.html#method.builder).
+///
+/// The sentiment of the input is determined by the analyzer `A`, which defaults to
+/// [`Afinn`](analyzer/struct.Afinn.html). Refer to
+/// [`PrintrBuilder::analyzer`](struct.PrintrBuilder.html#method.analyzer) to use a different
+/// one.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Printr<A = Afinn> {
     // the input `STRING`, if the `-f` is supplied, this will contain the contents of the file
     string: Vec<String>,
     // the final sentiment of the `string`
     sentiment: Option<Sentiment>,
     // the output string that will be displayed
     output_string: Option<String>,
+    // whether a `\c` escape was encountered, in which case no further output is produced
+    stopped: bool,
+    // the length of the prefix at the start of the `output_string`, once it is inserted
+    prefix_len: usize,
     // configuration
     config: Config,
+    // the analyzer used to determine the sentiment of the `string`
+    analyzer: A,
 }
 
 impl Printr {
-    /// Create a new object of this struct.
+    /// Start building a new object of this struct. Every option that is not set on the
+    /// builder is off, so the input is printed as `echo` would, colored by its sentiment.
+    ///
+    /// ```
+    /// use printr::{Color, Printr};
+    ///
+    /// let mut printr = Printr::builder()
+    ///     .string(vec!["Hello".to_string()])
+    ///     .color(Some(Color::Yellow))
+    ///     .newline(false)
+    ///     .build()
+    ///     .unwrap();
+    /// printr.run_all_handles();
+    /// assert_eq!(printr.get_output_string(), "\u{1b}[33mHello\u{1b}[0m");
+    /// ```
+    pub fn builder() -> PrintrBuilder {
+        PrintrBuilder::default()
+    }
+}
+
+/// A builder for [`Printr`](struct.Printr.html) objects, which sets every option by its
+/// name. The options correspond to the switches and flags of the command line application.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct PrintrBuilder<A = Afinn> {
+    // the file the input is read from, `STDIN` for stdin
+    file: Option<String>,
+    // the input `STRING`
+    string: Option<Vec<String>>,
+    // configuration
+    config: Config,
+    // the analyzer used to determine the sentiment of the input
+    analyzer: A,
+}
+
+impl<A: SentimentAnalyzer> PrintrBuilder<A> {
+    /// Interpret backslash escapes in the input, like `-e` does.
+    pub fn interpretations(mut self, interpretations: bool) -> Self {
+        self.config.interpretations = interpretations;
+        self
+    }
+    /// Put a newline at the end of the output, which is the default. Use `false` to leave
+    /// it out, like `-n` does.
+    pub fn newline(mut self, newline: bool) -> Self {
+        self.config.newline = newline;
+        self
+    }
+    /// Leave the output completely plain, ignoring its sentiment.
+    pub fn plain(mut self, plain: bool) -> Self {
+        self.config.plain = plain;
+        self
+    }
+    /// Separate the strings of the input by spaces, which is the default. Use `false` to
+    /// join them without spaces, like `-s` does.
+    pub fn spaces(mut self, spaces: bool) -> Self {
+        self.config.spaces = spaces;
+        self
+    }
+    /// Read the input from the file at `path`, or from stdin if it is
+    /// [`STDIN`](constant.STDIN.html). A single trailing newline of the input is stripped.
+    pub fn file(mut self, path: String) -> Self {
+        self.file = Some(path);
+        self
+    }
+    /// Use `string` as the input. Its strings are separated by spaces in the output.
+    pub fn string(mut self, string: Vec<String>) -> Self {
+        self.string = Some(string);
+        self
+    }
+    /// Color the output with `color` instead of the color guessed from its sentiment.
+    pub fn color(mut self, color: Option<Color>) -> Self {
+        self.config.color = color;
+        self
+    }
+    /// Apply all the formats of `format` to the output.
+    pub fn format(mut self, format: Vec<Format>) -> Self {
+        self.config.format = format;
+        self
+    }
+    /// Replace all the options set so far, except the input and the analyzer, by `config`.
+    pub fn config(mut self, config: Config) -> Self {
+        self.config = config;
+        self
+    }
+    /// Use `analyzer` instead of the current one to determine the sentiment of the input.
+    pub fn analyzer<B: SentimentAnalyzer>(self, analyzer: B) -> PrintrBuilder<B> {
+        PrintrBuilder {
+            file: self.file,
+            string: self.string,
+            config: self.config,
+            analyzer,
+        }
+    }
+    /// Color every word of the output by its own sentiment score instead of coloring the
+    /// whole output by the overall sentiment.
+    pub fn words(mut self, words: bool) -> Self {
+        self.config.words = words;
+        self
+    }
+    /// Use `thresholds` to decide whether the sentiment of the input is neutral, instead of
+    /// only treating exactly balanced scores as neutral.
+    pub fn thresholds(mut self, thresholds: Thresholds) -> Self {
+        self.config.thresholds = thresholds;
+        self
+    }
+    /// Map the guessed sentiment of the input to the colors of `colors` instead of the
+    /// default green, blue and red.
+    pub fn colors(mut self, colors: ColorMap) -> Self {
+        self.config.colors = colors;
+        self
+    }
+    /// Color the output on a continuous truecolor scale from red through a pale neutral
+    /// color to green, depending on how positive or negative the input is, instead of
+    /// mapping its sentiment to one of a few colors.
+    pub fn gradient(mut self, gradient: bool) -> Self {
+        self.config.gradient = gradient;
+        self
+    }
+    /// Display the output on `background`. If this is `Background::Sentiment`, the guessed
+    /// color of the input is used as the background (and the text is black, unless a color
+    /// was supplied) instead of the foreground.
+    pub fn background(mut self, background: Option<Background>) -> Self {
+        self.config.background = background;
+        self
+    }
+    /// Whether any colors or formats should be applied to the output. If this is `false`,
+    /// the output is left completely plain, which is useful when it is not written to a
+    /// terminal. Refer to [`ColorMode`](enum.ColorMode.html) to decide this.
+    pub fn styling(mut self, styled: bool) -> Self {
+        self.config.styled = styled;
+        self
+    }
+    /// Put `prefix` before the output. Every `{icon}` in it is replaced by the icon of the
+    /// sentiment tier of the input from `icons`, and every `{tier}` by the name of the tier.
+    /// Refer to [`Theme`](theme/struct.Theme.html) for an example.
+    pub fn prefix(mut self, prefix: String, icons: Icons) -> Self {
+        self.config.prefix = prefix;
+        self.config.icons = icons;
+        self
+    }
+    /// Create the [`Printr`](struct.Printr.html) object, reading the input file if one was
+    /// supplied. This fails if options that contradict each other were set, e.g. a file and
+    /// a string or a color and `plain`, or if the input file cannot be read or is not valid
+    /// UTF-8.
     ///
     /// **NOTE:** None of the handles are run at this point, so the output string will be
     /// unformatted. Refer to [`run_all_handles`](struct.Printr.html#method.run_all_handles).
-    #[allow(clippy::too_many_arguments)]
-    pub fn new(
-        interpretations: bool,
-        newline: bool,
-        plain: bool,
-        spaces: bool,
-        file: Option<String>,
-        color: Option<Color>,
-        string: Option<Vec<String>>,
-        format: Option<Format>,
-    ) -> Self {
-        let string = match file {
-            Some(f) => {
-                let contents = read_to_string(&f).unwrap_or_else(|err| {
-                    eprintln!("Could not find file: {}", err);
-                    process::exit(1);
-                });
-                vec![contents]
-            }
-            None => match string {
+    pub fn build(self) -> Result<Printr<A>, Error> {
+        let config = &self.config;
+        let conflicts = [
+            (
+                self.file.is_some() && self.string.is_some(),
+                "a file",
+                "a string",
+            ),
+            (config.plain && config.color.is_some(), "plain", "a color"),
+            (config.plain && config.words, "plain", "words"),
+            (config.plain && config.gradient, "plain", "gradient"),
+            (
+                config.plain && config.background.is_some(),
+                "plain",
+                "a background",
+            ),
+            (config.words && config.color.is_some(), "words", "a color"),
+            (config.words && config.gradient, "words", "gradient"),
+            (
+                config.words && config.background.is_some(),
+                "words",
+                "a background",
+            ),
+            (
+                config.gradient && config.color.is_some(),
+                "gradient",
+                "a color",
+            ),
+        ];
+        if let Some((_, first, second)) = conflicts.iter().find(|(conflict, ..)| *conflict) {
+            let msg = format!("{} and {} cannot be used together", first, second);
+            return Err(Error::InvalidOption(msg));
+        }
+        let string = match self.file {
+            Some(f) => vec![read_input(&f)?],
+            None => match self.string {
                 Some(s) => s,
                 None => vec![String::new()],
             },
         };
-        let config = Config::new(interpretations, newline, spaces, plain, color, format);
-        Self {
+        Ok(Printr {
             string,
             sentiment: None,
-            config,
             output_string: None,
-        }
+            stopped: false,
+            prefix_len: 0,
+            config: self.config,
+            analyzer: self.analyzer,
+        })
     }
-    /// We perform sentiment analysis.
+}
+
+impl<A: SentimentAnalyzer> Printr<A> {
+    /// The options of this object.
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+    // start over with `string` as the input and the color and background that were supplied
+    fn reset(&mut self, string: Vec<String>, color: Option<Color>, background: Option<Background>) {
+        self.string = string;
+        self.sentiment = None;
+        self.output_string = None;
+        self.stopped = false;
+        self.prefix_len = 0;
+        self.config.color = color;
+        self.config.background = background;
+    }
+    /// We perform sentiment analysis. If `-e` is supplied, the escapes are interpreted
+    /// first, so that only the text that is printed is scored.
     pub fn determine_sentiment(&mut self) {
-        let sentiment = Sentiment::new(self.string.clone());
+        let sentiment = self.analyzer.analyze(&self.analyzed_text());
         self.sentiment = Some(sentiment);
     }
+    // the input as it is scored by the analyzer
+    fn analyzed_text(&self) -> String {
+        let text = self.string.join(" ");
+        match self.config.interpretations {
+            true => escape::interpret(&text).string,
+            false => text,
+        }
+    }
     /// We handle the `-s` option here.
     pub fn handle_spaces(&mut self) {
         if self.config.spaces {
-            self.output_string = Some(self.string.join(""))
-        } else {
             self.output_string = Some(self.string.join(" "))
+        } else {
+            self.output_string = Some(self.string.join(""))
         }
     }
     /// We handle the `-e` and `-E` options here.
     pub fn handle_interpretations(&mut self) {
         if self.config.interpretations {
-            self.output_string = Some(self.output_string.clone().unwrap().replace(r"\\", r"\"));
+            let interpreted = escape::interpret(self.output_string.as_deref().unwrap_or_default());
+            self.output_string = Some(interpreted.string);
+            self.stopped = interpreted.stopped;
         }
     }
     /// We determine the color that should be applied to the output.
     pub fn determine_color(&mut self) {
-        if !self.config.plain && self.config.color.is_none() {
-            self.config.color = Some(determine_color(&self.sentiment.clone().unwrap()));
+        if self.config.plain || self.config.words {
+            return;
+        }
+        let sentiment = self.sentiment.as_ref().unwrap();
+        let guessed = match self.config.gradient {
+            true => gradient_color(sentiment, &self.config.thresholds),
+            false => determine_color(sentiment, &self.config.thresholds, &self.config.colors),
+        };
+        if self.config.background == Some(Background::Sentiment) {
+            self.config.background = Some(Background::Color(guessed));
+            self.config.color.get_or_insert(Color::Black);
+        } else if self.config.color.is_none() {
+            self.config.color = Some(guessed);
         }
     }
-    /// We handle the `-c` option here.
-    pub fn handle_coloring(&mut self) {
-        self.output_string = match self.config.color {
-            Some(Color::Blue) => Some(Blue.paint(self.output_string.clone().unwrap()).to_string()),
-            Some(Color::Red) => Some(Red.paint(self.output_string.clone().unwrap()).to_string()),
-            Some(Color::Green) => {
-                Some(Green.paint(self.output_string.clone().unwrap()).to_string())
-            }
-            Some(Color::Yellow) => Some(
-                Yellow
-                    .paint(self.output_string.clone().unwrap())
-                    .to_string(),
-            ),
-            Some(Color::Cyan) => Some(Cyan.paint(self.output_string.clone().unwrap()).to_string()),
-            None => self.output_string.clone(),
+    /// We put the prefix of the theme before the output here.
+    pub fn handle_prefix(&mut self) {
+        let prefix = self.rendered_prefix();
+        if let (Some(output), false) = (self.output_string.as_mut(), prefix.is_empty()) {
+            output.insert_str(0, &prefix);
+            self.prefix_len = prefix.len();
+        }
+    }
+    // the prefix with its placeholders replaced
+    fn rendered_prefix(&self) -> String {
+        if self.config.prefix.is_empty() {
+            return String::new();
+        }
+        let sentiment = self.sentiment.as_ref().unwrap();
+        let strong_threshold = self.config.colors.strong_threshold;
+        let tier = Tier::of(sentiment, &self.config.thresholds, strong_threshold);
+        let icons = &self.config.icons;
+        let icon = match tier {
+            Tier::StronglyPositive | Tier::Positive => &icons.positive,
+            Tier::Neutral => &icons.neutral,
+            Tier::Negative | Tier::StronglyNegative => &icons.negative,
         };
+        self.config
+            .prefix
+            .replace("{icon}", icon)
+            .replace("{tier}", tier.name())
     }
-    /// We handle the `-f` option here.#
-    pub fn handle_formatting(&mut self) {
-        self.output_string = match self.config.format {
-            Some(Format::Bold) => Some(
-                Style::new()
-                    .bold()
-                    .paint(self.output_string.clone().unwrap())
-                    .to_string(),
-            ),
-            Some(Format::Underline) => Some(
-                Style::new()
-                    .underline()
-                    .paint(self.output_string.clone().unwrap())
-                    .to_string(),
-            ),
-            Some(Format::Dimmed) => Some(
-                Style::new()
-                    .dimmed()
-                    .paint(self.output_string.clone().unwrap())
-                    .to_string(),
-            ),
-            Some(Format::Strikethrough) => Some(
-                Style::new()
-                    .strikethrough()
-                    .paint(self.output_string.clone().unwrap())
-                    .to_string(),
-            ),
-            None => self.output_string.clone(),
+    /// We handle the `-c`, `-b` and `-f` options here. The colors and formats are combined
+    /// into a single style, which is applied to the whole output.
+    pub fn handle_coloring(&mut self) {
+        if !self.config.styled {
+            return;
+        }
+        if self.config.words && !self.config.plain {
+            self.handle_word_coloring();
+            return;
+        }
+        let mut style = self.format_style();
+        if let Some(color) = &self.config.color {
+            style = style.fg(color.colour());
+        }
+        if let Some(Background::Color(background)) = &self.config.background {
+            style = style.on(background.colour());
+        }
+        if style != Style::new() {
+            let output = self.output_string.take().unwrap_or_default();
+            self.output_string = Some(style.paint(output).to_string());
         }
     }
+    /// We handle the `-w` option here. Each token found by the sentiment analysis is looked
+    /// up in the output (in order) and painted in the color of the tier of its own score.
+    /// Neutral words are not colored.
+    fn handle_word_coloring(&mut self) {
+        let output = self.output_string.take().unwrap_or_default();
+        let sentiment = self.sentiment.as_ref().unwrap();
+        let style = self.format_style();
+        let mut painted = String::with_capacity(output.len());
+        // the prefix is not part of the analysed text, so none of its words are colored
+        let (prefix, mut rest) = output.split_at(self.prefix_len);
+        painted.push_str(&style.paint(prefix).to_string());
+        for token in &sentiment.tokens {
+            let index = match rest.find(token.text.as_str()) {
+                Some(index) => index,
+                None => continue,
+            };
+            painted.push_str(&style.paint(&rest[..index]).to_string());
+            let colors = &self.config.colors;
+            let word_style = match Tier::of_word(token.score, colors.strong_threshold) {
+                Tier::Neutral => style,
+                tier => style.fg(colors.color_of(tier).colour()),
+            };
+            painted.push_str(&word_style.paint(token.text.as_str()).to_string());
+            rest = &rest[index + token.text.len()..];
+        }
+        painted.push_str(&style.paint(rest).to_string());
+        self.output_string = Some(painted);
+    }
+    // the style with all the formats of the `-f` option
+    fn format_style(&self) -> Style {
+        self.config
+            .format
+            .iter()
+            .fold(Style::new(), |style, format| format.apply(style))
+    }
     /// We handle the `-n` option here.
     pub fn handle_newline(&mut self) {
-        if !self.config.newline {
-            self.output_string = Some(format!("{}\n", self.output_string.clone().unwrap()));
+        if self.config.newline && !self.stopped {
+            if let Some(output) = self.output_string.as_mut() {
+                output.push('\n');
+            }
         }
     }
     /// This runs all the above functions so that all the switches and flags are accounted
@@ -183,80 +576,554 @@ impl Printr {
         self.handle_spaces();
         self.handle_interpretations();
         self.determine_color();
+        self.handle_prefix();
         self.handle_coloring();
         self.handle_newline();
-        self.handle_formatting();
+    }
+    /// This method returns a JSON report explaining how the color of the output was chosen.
+    /// It contains the positivity and negativity scores, the polarity, every word that
+    /// contributed to the sentiment along with its weight and the chosen foreground and
+    /// background colors (which are `null` if the output is plain or every word is colored
+    /// separately).
+    ///
+    /// **NOTE:** This should only be called after
+    /// [`run_all_handles`](struct.Printr.html#method.run_all_handles).
+    pub fn explain(&self) -> String {
+        let sentiment = self.sentiment.clone().unwrap_or_default();
+        let polarity = match sentiment.get_polarity(&self.config.thresholds) {
+            1 => "positive",
+            -1 => "negative",
+            _ => "neutral",
+        };
+        let words: Vec<_> = sentiment
+            .tokens
+            .iter()
+            .filter(|token| token.score != 0.0)
+            .map(|token| json!({ "word": token.text, "weight": token.score }))
+            .collect();
+        let (color, background) =
+            match self.config.plain || self.config.words || !self.config.styled {
+                true => (None, None),
+                false => (
+                    self.config.color.as_ref().map(Color::to_string),
+                    match &self.config.background {
+                        Some(Background::Color(background)) => Some(background.to_string()),
+                        _ => None,
+                    },
+                ),
+            };
+        json!({
+            "text": self.analyzed_text(),
+            "positive": sentiment.positive,
+            "negative": sentiment.negative,
+            "polarity": polarity,
+            "words": words,
+            "color": color,
+            "background": background,
+        })
+        .to_string()
     }
     /// This method will return the final string that should be displayed.
     pub fn get_output_string(self) -> String {
-        match self.output_string {
-            Some(s) => s,
-            None => "".to_string(),
-        }
+        self.output_string.unwrap_or_default()
+    }
+    /// Write the final string that should be displayed to `writer`, e.g. a file, a socket or
+    /// a buffer. Unlike [`get_output_string`](struct.Printr.html#method.get_output_string),
+    /// this does not consume the object, so it can be written to several places.
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(self.output_string.as_deref().unwrap_or_default().as_bytes())
     }
 }
 
-fn determine_color(sentiment: &Sentiment) -> Color {
-    let polarity = sentiment.clone().get_polarity();
-    if polarity == 1 {
-        Color::Green
-    } else if polarity == -1 {
-        Color::Red
+/// Displays the final string, which is empty until
+/// [`run_all_handles`](struct.Printr.html#method.run_all_handles) is called.
+impl<A> fmt::Display for Printr<A> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.output_string.as_deref().unwrap_or_default())
+    }
+}
+
+/// Read all of the file at `path`, or of stdin if it is `STDIN`. A single trailing newline is
+/// stripped, the same way the shell does for command substitution, so that `printr` does not
+/// print an extra blank line when the input ends with a newline, as files usually do.
+fn read_input(path: &str) -> Result<String, Error> {
+    let (bytes, name) = if path == STDIN {
+        let mut bytes = vec![];
+        io::stdin().read_to_end(&mut bytes)?;
+        (bytes, "stdin")
     } else {
-        Color::Blue
+        let bytes = fs::read(path)
+            .map_err(|err| io::Error::new(err.kind(), format!("{}: {}", path, err)))?;
+        (bytes, path)
+    };
+    let mut contents = String::from_utf8(bytes).map_err(|_| Error::Encoding(name.to_string()))?;
+    if contents.ends_with('\n') {
+        contents.pop();
+        if contents.ends_with('\r') {
+            contents.pop();
+        }
     }
+    Ok(contents)
 }
 
-#[derive(Debug, PartialEq, Clone)]
-struct Sentiment(
-    // the positivity score of the sentence being analysed
-    f32,
-    // the negativity score of the sentence being analysed
-    f32,
-);
-
-impl Sentiment {
-    fn new(string: Vec<String>) -> Self {
-        let string = string.join(" ");
-        let analyser = sentiment::analyze(string);
-        Self(analyser.positive.score, analyser.negative.score)
-    }
-    fn get_polarity(self) -> i8 {
-        if (self.0 - self.1).abs() < EPSILON {
-            0
-        } else if self.0 > self.1 {
-            1
-        } else {
-            -1
+fn determine_color(sentiment: &Sentiment, thresholds: &Thresholds, colors: &ColorMap) -> Color {
+    let tier = Tier::of(sentiment, thresholds, colors.strong_threshold);
+    colors.color_of(tier).clone()
+}
+
+// the tiers that the guessed sentiment is divided into
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Tier {
+    StronglyPositive,
+    Positive,
+    Neutral,
+    Negative,
+    StronglyNegative,
+}
+
+impl Tier {
+    // the tier of `sentiment`, which is only ever strong if `strong_threshold` is supplied
+    fn of(sentiment: &Sentiment, thresholds: &Thresholds, strong_threshold: Option<f32>) -> Self {
+        let net = sentiment.positive - sentiment.negative;
+        let strong = strong_threshold.is_some_and(|threshold| net.abs() >= threshold);
+        match (sentiment.get_polarity(thresholds), strong) {
+            (1, true) => Tier::StronglyPositive,
+            (1, false) => Tier::Positive,
+            (-1, true) => Tier::StronglyNegative,
+            (-1, false) => Tier::Negative,
+            _ => Tier::Neutral,
+        }
+    }
+
+    // the tier of a single word scored `score`
+    fn of_word(score: f32, strong_threshold: Option<f32>) -> Self {
+        let strong = strong_threshold.is_some_and(|threshold| score.abs() >= threshold);
+        match (score > 0.0, score < 0.0, strong) {
+            (true, _, true) => Tier::StronglyPositive,
+            (true, _, false) => Tier::Positive,
+            (_, true, true) => Tier::StronglyNegative,
+            (_, true, false) => Tier::Negative,
+            _ => Tier::Neutral,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Tier::StronglyPositive => "strongly positive",
+            Tier::Positive => "positive",
+            Tier::Neutral => "neutral",
+            Tier::Negative => "negative",
+            Tier::StronglyNegative => "strongly negative",
         }
     }
 }
 
+/// The net score from which the gradient is at its most vivid color.
+const GRADIENT_SCALE: f32 = 5.0;
+/// The colors at the neutral, positive and negative ends of the gradient.
+const GRADIENT_NEUTRAL: (u8, u8, u8) = (204, 204, 204);
+const GRADIENT_POSITIVE: (u8, u8, u8) = (0, 215, 0);
+const GRADIENT_NEGATIVE: (u8, u8, u8) = (215, 0, 0);
+
+fn gradient_color(sentiment: &Sentiment, thresholds: &Thresholds) -> Color {
+    let net = sentiment.positive - sentiment.negative;
+    let end = match sentiment.get_polarity(thresholds) {
+        1 => GRADIENT_POSITIVE,
+        -1 => GRADIENT_NEGATIVE,
+        _ => GRADIENT_NEUTRAL,
+    };
+    let t = (net.abs() / GRADIENT_SCALE).min(1.0);
+    let mix = |from: u8, to: u8| (from as f32 + (to as f32 - from as f32) * t).round() as u8;
+    let (r, g, b) = GRADIENT_NEUTRAL;
+    Color::Rgb(mix(r, end.0), mix(g, end.1), mix(b, end.2))
+}
+
 /// The colors in which the output can be displayed in.
-#[derive(Debug, PartialEq)]
+///
+/// Besides the eight basic ANSI colors, a color can be one of the 256 colors of the extended
+/// palette or a 24-bit (truecolor) RGB value. Not every terminal emulator supports the
+/// latter two.
+#[derive(Debug, PartialEq, Clone)]
 pub enum Color {
+    Black,
     Red,
-    Blue,
     Green,
     Yellow,
+    Blue,
+    Magenta,
     Cyan,
+    White,
+    /// A color of the 256 color palette, by its index.
+    Fixed(u8),
+    /// A 24-bit color, by its red, green and blue components.
+    Rgb(u8, u8, u8),
+}
+
+impl Color {
+    // the `ansi_term` counterpart of this color
+    fn colour(&self) -> Colour {
+        match *self {
+            Color::Black => Colour::Black,
+            Color::Red => Colour::Red,
+            Color::Green => Colour::Green,
+            Color::Yellow => Colour::Yellow,
+            Color::Blue => Colour::Blue,
+            Color::Magenta => Colour::Purple,
+            Color::Cyan => Colour::Cyan,
+            Color::White => Colour::White,
+            Color::Fixed(index) => Colour::Fixed(index),
+            Color::Rgb(r, g, b) => Colour::RGB(r, g, b),
+        }
+    }
+}
+
+impl FromStr for Color {
+    type Err = String;
+
+    /// Parse a color from its name (e.g. `red`), its index in the 256 color palette (e.g.
+    /// `208`), a hex code (e.g. `#ff8800`) or its RGB components (e.g. `rgb(255, 136, 0)`).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let value = s.trim().to_lowercase();
+        let color = match value.as_str() {
+            "black" => Color::Black,
+            "red" => Color::Red,
+            "green" => Color::Green,
+            "yellow" => Color::Yellow,
+            "blue" => Color::Blue,
+            "magenta" => Color::Magenta,
+            "cyan" => Color::Cyan,
+            "white" => Color::White,
+            _ => {
+                if let Some(hex) = value.strip_prefix('#') {
+                    let component = |index: usize| {
+                        hex.get(index..index + 2)
+                            .and_then(|c| u8::from_str_radix(c, 16).ok())
+                    };
+                    match (hex.len(), component(0), component(2), component(4)) {
+                        (6, Some(r), Some(g), Some(b)) => Color::Rgb(r, g, b),
+                        _ => return Err(format!("invalid hex color {:?}", s)),
+                    }
+                } else if let Some(rgb) = value
+                    .strip_prefix("rgb(")
+                    .and_then(|rgb| rgb.strip_suffix(')'))
+                {
+                    let components: Vec<_> = rgb.split(',').map(|c| c.trim().parse()).collect();
+                    match components.as_slice() {
+                        [Ok(r), Ok(g), Ok(b)] => Color::Rgb(*r, *g, *b),
+                        _ => return Err(format!("invalid RGB color {:?}", s)),
+                    }
+                } else if let Ok(index) = value.parse() {
+                    Color::Fixed(index)
+                } else {
+                    return Err(format!(
+                        "unknown color {:?}, expected a name, a number from 0 to 255, a hex \
+                         code like #ff8800 or rgb(255, 136, 0)",
+                        s
+                    ));
+                }
+            }
+        };
+        Ok(color)
+    }
+}
+
+impl fmt::Display for Color {
+    /// Display the name of a basic color (e.g. `red`), the index of a color of the 256 color
+    /// palette or the hex code of an RGB color (e.g. `#ff8800`), which can all be parsed
+    /// back into the color.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Color::Black => f.write_str("black"),
+            Color::Red => f.write_str("red"),
+            Color::Green => f.write_str("green"),
+            Color::Yellow => f.write_str("yellow"),
+            Color::Blue => f.write_str("blue"),
+            Color::Magenta => f.write_str("magenta"),
+            Color::Cyan => f.write_str("cyan"),
+            Color::White => f.write_str("white"),
+            Color::Fixed(index) => write!(f, "{}", index),
+            Color::Rgb(r, g, b) => write!(f, "#{:02x}{:02x}{:02x}", r, g, b),
+        }
+    }
+}
+
+/// The colors that each tier of the guessed sentiment is displayed in.
+///
+/// By default, there are three tiers: positive, neutral and negative. If the
+/// `strong_threshold` is set, there are five, since a text whose net score (its positivity
+/// minus its negativity score) is at least this far from zero is strongly positive or
+/// strongly negative.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default, rename_all = "kebab-case")
+)]
+pub struct ColorMap {
+    /// The color of strongly positive text.
+    pub strongly_positive: Color,
+    /// The color of positive text.
+    pub positive: Color,
+    /// The color of neutral text.
+    pub neutral: Color,
+    /// The color of negative text.
+    pub negative: Color,
+    /// The color of strongly negative text.
+    pub strongly_negative: Color,
+    /// The net score from which text is strongly positive or negative, if five tiers are
+    /// used.
+    pub strong_threshold: Option<f32>,
+}
+
+impl ColorMap {
+    /// The net score from which text is strongly positive or negative by default.
+    pub const STRONG_THRESHOLD: f32 = 4.0;
+
+    // the color of `tier`
+    fn color_of(&self, tier: Tier) -> &Color {
+        match tier {
+            Tier::StronglyPositive => &self.strongly_positive,
+            Tier::Positive => &self.positive,
+            Tier::Neutral => &self.neutral,
+            Tier::Negative => &self.negative,
+            Tier::StronglyNegative => &self.strongly_negative,
+        }
+    }
+
+    /// The default five tier mapping, from strongly positive to strongly negative: green,
+    /// cyan, blue, yellow and red.
+    pub fn five_tiers() -> Self {
+        Self {
+            strongly_positive: Color::Green,
+            positive: Color::Cyan,
+            neutral: Color::Blue,
+            negative: Color::Yellow,
+            strongly_negative: Color::Red,
+            strong_threshold: Some(Self::STRONG_THRESHOLD),
+        }
+    }
+}
+
+impl Default for ColorMap {
+    /// The default three tier mapping: green, blue and red.
+    fn default() -> Self {
+        Self {
+            strongly_positive: Color::Green,
+            positive: Color::Green,
+            neutral: Color::Blue,
+            negative: Color::Red,
+            strongly_negative: Color::Red,
+            strong_threshold: None,
+        }
+    }
+}
+
+/// The background that the output can be displayed on.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Background {
+    /// A fixed color.
+    Color(Color),
+    /// The color guessed from the sentiment of the input.
+    Sentiment,
+}
+
+impl FromStr for Background {
+    type Err = String;
+
+    /// Parse a background from `sentiment` or a [`Color`](enum.Color.html).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "sentiment" => Ok(Background::Sentiment),
+            _ => s.parse().map(Background::Color),
+        }
+    }
+}
+
+impl fmt::Display for Background {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Background::Color(color) => write!(f, "{}", color),
+            Background::Sentiment => f.write_str("sentiment"),
+        }
+    }
+}
+
+/// When the output should be styled, i.e. colored and formatted.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum ColorMode {
+    /// Style the output only if it is written to a terminal, unless the environment says
+    /// otherwise. Refer to [`should_style`](#method.should_style) for the details.
+    #[default]
+    Auto,
+    /// Always style the output.
+    Always,
+    /// Never style the output.
+    Never,
+}
+
+impl ColorMode {
+    /// Whether output written to a stream should be styled, `is_terminal` being whether the
+    /// stream is a terminal. In `Auto` mode, the following environment variables are
+    /// honored, in order of precedence:
+    ///
+    /// - `CLICOLOR_FORCE` set to anything but `0` always styles the output.
+    /// - `NO_COLOR` set to anything but an empty string never styles the output.
+    /// - `CLICOLOR=0` never styles the output.
+    /// - `TERM=dumb` never styles the output.
+    pub fn should_style(self, is_terminal: bool) -> bool {
+        self.should_style_with(is_terminal, |name| std::env::var(name).ok())
+    }
+
+    // `should_style`, with the environment variables looked up by `var`
+    fn should_style_with<F: Fn(&str) -> Option<String>>(self, is_terminal: bool, var: F) -> bool {
+        match self {
+            ColorMode::Always => return true,
+            ColorMode::Never => return false,
+            ColorMode::Auto => {}
+        }
+        if var("CLICOLOR_FORCE").is_some_and(|value| value != "0") {
+            return true;
+        }
+        let disabled = var("NO_COLOR").is_some_and(|value| !value.is_empty())
+            || var("CLICOLOR").as_deref() == Some("0")
+            || var("TERM").as_deref() == Some("dumb");
+        !disabled && is_terminal
+    }
+}
+
+impl FromStr for ColorMode {
+    type Err = String;
+
+    /// Parse a color mode from its name, e.g. `auto`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "auto" => Ok(ColorMode::Auto),
+            "always" => Ok(ColorMode::Always),
+            "never" => Ok(ColorMode::Never),
+            _ => Err(format!("unknown color mode {:?}", s)),
+        }
+    }
 }
 
-/// The formats in which the output can be displayed in.
-#[derive(Debug, PartialEq)]
+impl fmt::Display for ColorMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ColorMode::Auto => f.write_str("auto"),
+            ColorMode::Always => f.write_str("always"),
+            ColorMode::Never => f.write_str("never"),
+        }
+    }
+}
+
+/// The formats in which the output can be displayed in. Any number of them can be combined.
+#[derive(Debug, PartialEq, Clone)]
 pub enum Format {
     Bold,
+    Dimmed,
+    Italic,
     Underline,
+    Blink,
+    Reverse,
+    Hidden,
     Strikethrough,
-    Dimmed,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    /// Parse a format from its name, e.g. `bold`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let format = match s.trim().to_lowercase().as_str() {
+            "bold" => Format::Bold,
+            "dimmed" => Format::Dimmed,
+            "italic" => Format::Italic,
+            "underline" => Format::Underline,
+            "blink" => Format::Blink,
+            "reverse" => Format::Reverse,
+            "hidden" => Format::Hidden,
+            "strikethrough" => Format::Strikethrough,
+            _ => return Err(format!("unknown format {:?}", s)),
+        };
+        Ok(format)
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Format::Bold => "bold",
+            Format::Dimmed => "dimmed",
+            Format::Italic => "italic",
+            Format::Underline => "underline",
+            Format::Blink => "blink",
+            Format::Reverse => "reverse",
+            Format::Hidden => "hidden",
+            Format::Strikethrough => "strikethrough",
+        };
+        f.write_str(name)
+    }
+}
+
+// (de)serialize these types as the strings they are displayed as and parsed from
+#[cfg(feature = "serde")]
+macro_rules! serde_as_string {
+    ($($ty:ty),*) => {$(
+        impl serde::Serialize for $ty {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_str(self)
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for $ty {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let value = String::deserialize(deserializer)?;
+                value.parse().map_err(serde::de::Error::custom)
+            }
+        }
+    )*};
+}
+
+#[cfg(feature = "serde")]
+serde_as_string!(Color, Background, ColorMode, Format);
+
+impl Format {
+    // add this format to `style`
+    fn apply(&self, style: Style) -> Style {
+        match self {
+            Format::Bold => style.bold(),
+            Format::Dimmed => style.dimmed(),
+            Format::Italic => style.italic(),
+            Format::Underline => style.underline(),
+            Format::Blink => style.blink(),
+            Format::Reverse => style.reverse(),
+            Format::Hidden => style.hidden(),
+            Format::Strikethrough => style.strikethrough(),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::{error::Error, io::Write};
+    use analyzer::Token;
+    use std::{
+        error::Error,
+        io::{Cursor, Write},
+    };
     use tempfile::NamedTempFile;
 
+    // build and run the printr object of `builder`
+    pub(crate) fn run_printr<A: SentimentAnalyzer>(builder: PrintrBuilder<A>) -> Printr<A> {
+        let mut printr = builder.build().unwrap();
+        printr.run_all_handles();
+        printr
+    }
+
+    // a builder with `text` as the input
+    pub(crate) fn input(text: &str) -> PrintrBuilder {
+        Printr::builder().string(vec![text.to_string()])
+    }
+
     // we keep using the word "zealous" here since we know it's
     // [afinn](https://github.com/fnielsen/afinn) score is 2.0 and can be used consistently for
     // tests
@@ -266,66 +1133,442 @@ mod tests {
         let mut file = NamedTempFile::new()?;
         let content = "zealous";
         write!(file, "{}", content)?;
-        let mut printr = Printr::new(
-            true,
-            true,
-            false,
-            false,
-            Some(file.path().to_str().unwrap().to_string()),
-            None,
-            None,
-            None,
-        );
+        let mut printr = Printr::builder()
+            .interpretations(true)
+            .newline(false)
+            .file(file.path().to_str().unwrap().to_string())
+            .build()
+            .unwrap();
         printr.run_all_handles();
         assert_eq!(
             printr,
             Printr {
                 string: vec![content.to_string()],
-                sentiment: Some(Sentiment(2.0, 0.0)),
+                sentiment: Some(Sentiment {
+                    positive: 2.0,
+                    negative: 0.0,
+                    tokens: vec![Token::new("zealous", 2.0)],
+                }),
                 output_string: Some("\u{1b}[32mzealous\u{1b}[0m".to_string()),
+                stopped: false,
+                prefix_len: 0,
+                analyzer: Afinn::default(),
                 config: Config {
                     color: Some(Color::Green),
-                    format: None,
+                    format: vec![],
                     interpretations: true,
-                    newline: true,
+                    newline: false,
                     plain: false,
-                    spaces: false
+                    spaces: true,
+                    words: false,
+                    thresholds: Thresholds::default(),
+                    colors: ColorMap::default(),
+                    gradient: false,
+                    background: None,
+                    styled: true,
+                    prefix: String::new(),
+                    icons: Icons::default(),
                 }
             }
         );
         Ok(())
     }
 
+    #[test]
+    fn test_builder_rejects_conflicting_options() {
+        let err = Printr::builder()
+            .plain(true)
+            .color(Some(Color::Red))
+            .build()
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid options: plain and a color cannot be used together"
+        );
+        let err = Printr::builder()
+            .file(STDIN.to_string())
+            .string(vec!["zealous".to_string()])
+            .build()
+            .unwrap_err();
+        assert!(matches!(err, super::Error::InvalidOption(_)));
+        assert!(Printr::builder()
+            .words(true)
+            .gradient(true)
+            .build()
+            .is_err());
+        assert!(Printr::builder()
+            .gradient(true)
+            .background(None)
+            .build()
+            .is_ok());
+    }
+
+    #[test]
+    fn test_trailing_newline_of_input_file_is_stripped() -> Result<(), Box<dyn Error>> {
+        let mut file = NamedTempFile::new()?;
+        write!(file, "zealous\r\n")?;
+        let printr = Printr::builder()
+            .file(file.path().to_str().unwrap().to_string())
+            .build()?;
+        assert_eq!(printr.string, vec!["zealous".to_string()]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_input_file_errors_are_returned() -> Result<(), Box<dyn Error>> {
+        let mut file = NamedTempFile::new()?;
+        file.write_all(b"zealous \xff")?;
+        let path = file.path().to_str().unwrap().to_string();
+        let err = Printr::builder().file(path.clone()).build().unwrap_err();
+        assert!(matches!(err, super::Error::Encoding(input) if input == path));
+        let err = Printr::builder()
+            .file("/nonexistent/printr".to_string())
+            .build()
+            .unwrap_err();
+        assert!(matches!(err, super::Error::Io(_)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_colors_are_parsed() {
+        assert_eq!("Magenta".parse(), Ok(Color::Magenta));
+        assert_eq!("208".parse(), Ok(Color::Fixed(208)));
+        assert_eq!("#FF8800".parse(), Ok(Color::Rgb(255, 136, 0)));
+        assert_eq!("rgb(255, 136,0)".parse(), Ok(Color::Rgb(255, 136, 0)));
+        assert!("256".parse::<Color>().is_err());
+        assert!("#ff88".parse::<Color>().is_err());
+        assert!("rgb(1, 2)".parse::<Color>().is_err());
+        let printr = run_printr(input("zealous").color(Some(Color::Rgb(255, 136, 0))));
+        assert_eq!(
+            printr.to_string(),
+            "\u{1b}[38;2;255;136;0mzealous\u{1b}[0m\n"
+        );
+    }
+
+    #[test]
+    fn test_displayed_values_are_parsed_back() {
+        let colors = [Color::Magenta, Color::Fixed(208), Color::Rgb(255, 136, 0)];
+        for color in colors {
+            assert_eq!(color.to_string().parse(), Ok(color));
+        }
+        assert_eq!(Color::Rgb(255, 136, 0).to_string(), "#ff8800");
+        assert_eq!(
+            Format::Strikethrough.to_string().parse(),
+            Ok(Format::Strikethrough)
+        );
+        assert_eq!("Sentiment".parse(), Ok(Background::Sentiment));
+        assert_eq!("208".parse(), Ok(Background::Color(Color::Fixed(208))));
+        assert_eq!(ColorMode::Never.to_string().parse(), Ok(ColorMode::Never));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_config_is_deserialized() -> Result<(), Box<dyn Error>> {
+        let config: Config = serde_json::from_value(json!({
+            "color": "#ff8800",
+            "format": ["bold", "italic"],
+            "background": "sentiment",
+            "thresholds": { "neutral-band": 1.5 },
+            "colors": { "neutral": "cyan" },
+        }))?;
+        assert_eq!(
+            config,
+            Config {
+                color: Some(Color::Rgb(255, 136, 0)),
+                format: vec![Format::Bold, Format::Italic],
+                background: Some(Background::Sentiment),
+                thresholds: Thresholds {
+                    neutral_band: 1.5,
+                    ..Thresholds::default()
+                },
+                colors: ColorMap {
+                    neutral: Color::Cyan,
+                    ..ColorMap::default()
+                },
+                ..Config::default()
+            }
+        );
+        assert!(serde_json::from_value::<Config>(json!({ "color": "mauve" })).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_gradient_depends_on_the_net_score() {
+        let color_of = |text| run_printr(input(text).gradient(true)).config.color;
+        assert_eq!(color_of("badger"), Some(Color::Rgb(204, 204, 204)));
+        assert_eq!(color_of("zealous"), Some(Color::Rgb(122, 208, 122)));
+        assert_eq!(
+            color_of("zealous and outstanding"),
+            Some(Color::Rgb(0, 215, 0))
+        );
+        assert_eq!(color_of("bad"), Some(Color::Rgb(211, 82, 82)));
+    }
+
+    #[test]
+    fn test_background_follows_the_sentiment() {
+        let output_of = |color, background| {
+            run_printr(input("bad").color(color).background(Some(background))).to_string()
+        };
+        assert_eq!(
+            output_of(None, Background::Sentiment),
+            "\u{1b}[41;30mbad\u{1b}[0m\n"
+        );
+        assert_eq!(
+            output_of(Some(Color::White), Background::Sentiment),
+            "\u{1b}[41;37mbad\u{1b}[0m\n"
+        );
+        assert_eq!(
+            output_of(None, Background::Color(Color::Fixed(236))),
+            "\u{1b}[48;5;236;31mbad\u{1b}[0m\n"
+        );
+    }
+
+    #[test]
+    fn test_formats_are_combined_into_one_style() {
+        let printr = run_printr(input("bad").color(Some(Color::Red)).format(vec![
+            Format::Bold,
+            Format::Italic,
+            Format::Reverse,
+        ]));
+        assert_eq!(printr.to_string(), "\u{1b}[1;3;7;31mbad\u{1b}[0m\n");
+    }
+
+    #[test]
+    fn test_color_mode_honors_the_environment() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                vars.iter()
+                    .find(|(var, _)| *var == name)
+                    .map(|(_, value)| value.to_string())
+            }
+        };
+        assert!(ColorMode::Auto.should_style_with(true, env(&[])));
+        assert!(!ColorMode::Auto.should_style_with(false, env(&[])));
+        assert!(!ColorMode::Auto.should_style_with(true, env(&[("NO_COLOR", "1")])));
+        assert!(ColorMode::Auto.should_style_with(true, env(&[("NO_COLOR", "")])));
+        assert!(!ColorMode::Auto.should_style_with(true, env(&[("CLICOLOR", "0")])));
+        assert!(!ColorMode::Auto.should_style_with(true, env(&[("TERM", "dumb")])));
+        assert!(ColorMode::Auto
+            .should_style_with(false, env(&[("CLICOLOR_FORCE", "1"), ("NO_COLOR", "1")])));
+        assert!(ColorMode::Always.should_style_with(false, env(&[("NO_COLOR", "1")])));
+        assert!(!ColorMode::Never.should_style_with(true, env(&[("CLICOLOR_FORCE", "1")])));
+    }
+
+    #[test]
+    fn test_output_is_written_and_displayed() -> Result<(), Box<dyn Error>> {
+        let mut printr = input("bad").build().unwrap();
+        assert_eq!(printr.to_string(), "");
+        printr.run_all_handles();
+        let mut output = vec![];
+        printr.write_to(&mut output)?;
+        assert_eq!(String::from_utf8(output)?, "\u{1b}[31mbad\u{1b}[0m\n");
+        assert_eq!(printr.to_string(), "\u{1b}[31mbad\u{1b}[0m\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_unstyled_output_is_plain() {
+        let printr = run_printr(
+            input("bad")
+                .color(Some(Color::Red))
+                .format(vec![Format::Bold])
+                .styling(false),
+        );
+        assert_eq!(printr.to_string(), "bad\n");
+    }
+
+    #[test]
+    fn test_prefix_shows_the_tier() {
+        let theme = theme::Theme::builtin("monochrome").unwrap();
+        let output_of = |text, prefix: &str| {
+            let builder = input(text).prefix(prefix.to_string(), theme.icons.clone());
+            run_printr(builder.words(true)).to_string()
+        };
+        assert_eq!(output_of("bad", "{icon} "), "[-] \u{1b}[31mbad\u{1b}[0m\n");
+        assert_eq!(
+            output_of("negative vibes", "{tier}: "),
+            "negative: \u{1b}[31mnegative\u{1b}[0m vibes\n"
+        );
+        // without the prefix, the words are colored from the start of the output
+        let mut printr = input("bad")
+            .prefix("{tier}: ".to_string(), theme.icons.clone())
+            .words(true)
+            .build()
+            .unwrap();
+        printr.determine_sentiment();
+        printr.handle_spaces();
+        printr.handle_coloring();
+        assert_eq!(printr.to_string(), "\u{1b}[31mbad\u{1b}[0m");
+    }
+
+    #[test]
+    fn test_colors_are_mapped_by_tier() {
+        let colors = ColorMap {
+            positive: Color::Blue,
+            neutral: Color::Cyan,
+            ..ColorMap::five_tiers()
+        };
+        let color_of = |text| run_printr(input(text).colors(colors.clone())).config.color;
+        assert_eq!(color_of("zealous"), Some(Color::Blue));
+        assert_eq!(color_of("zealous and outstanding"), Some(Color::Green));
+        assert_eq!(color_of("badger"), Some(Color::Cyan));
+        assert_eq!(color_of("bad"), Some(Color::Yellow));
+        assert_eq!(color_of("terrible and bad"), Some(Color::Red));
+    }
+
     #[test]
     fn test_new_being_created_with_input_string_supplied() -> Result<(), Box<dyn Error>> {
         let content = String::from("zealous");
-        let mut printr = Printr::new(
-            true,
-            true,
-            false,
-            false,
-            None,
-            None,
-            Some(vec![content.clone()]),
-            Some(Format::Bold),
-        );
+        let mut printr = Printr::builder()
+            .interpretations(true)
+            .newline(false)
+            .string(vec![content.clone()])
+            .format(vec![Format::Bold])
+            .build()
+            .unwrap();
         printr.run_all_handles();
         assert_eq!(
             printr,
             Printr {
                 string: vec![content.clone()],
-                sentiment: Some(Sentiment(2.0, 0.0)),
-                output_string: Some("\u{1b}[1m\u{1b}[32mzealous\u{1b}[0m\u{1b}[0m".to_string()),
+                sentiment: Some(Sentiment {
+                    positive: 2.0,
+                    negative: 0.0,
+                    tokens: vec![Token::new("zealous", 2.0)],
+                }),
+                output_string: Some("\u{1b}[1;32mzealous\u{1b}[0m".to_string()),
+                stopped: false,
+                prefix_len: 0,
+                analyzer: Afinn::default(),
                 config: Config {
                     interpretations: true,
-                    newline: true,
+                    newline: false,
                     color: Some(Color::Green),
-                    spaces: false,
+                    spaces: true,
                     plain: false,
-                    format: Some(Format::Bold),
+                    format: vec![Format::Bold],
+                    words: false,
+                    thresholds: Thresholds::default(),
+                    colors: ColorMap::default(),
+                    gradient: false,
+                    background: None,
+                    styled: true,
+                    prefix: String::new(),
+                    icons: Icons::default(),
                 }
             }
         );
         Ok(())
     }
+
+    #[test]
+    fn test_newline_and_spaces_are_on_by_default() {
+        let strings = vec!["zealous".to_string(), "and".to_string()];
+        let output_of = |builder: PrintrBuilder| run_printr(builder.plain(true)).to_string();
+        let builder = || Printr::builder().string(strings.clone());
+        assert_eq!(output_of(builder()), "zealous and\n");
+        assert_eq!(
+            output_of(builder().newline(false).spaces(false)),
+            "zealousand"
+        );
+    }
+
+    #[test]
+    fn test_interpretations_stop_suppresses_newline() {
+        let printr = run_printr(
+            input(r"zealous\tand\cignored")
+                .interpretations(true)
+                .plain(true),
+        );
+        assert_eq!(printr.to_string(), "zealous\tand");
+    }
+
+    #[test]
+    fn test_interpreted_input_is_scored() {
+        let color_of = |text| run_printr(input(text).interpretations(true)).config.color;
+        assert_eq!(color_of(r"x\tfailed"), Some(Color::Red));
+        // the text after a `\c` is not printed, so it is not scored either
+        assert_eq!(color_of(r"x\cfailed"), Some(Color::Blue));
+        let printr = run_printr(input(r"Deploy\tfailed").interpretations(true).words(true));
+        assert_eq!(printr.to_string(), "Deploy\t\u{1b}[31mfailed\u{1b}[0m\n");
+    }
+
+    #[test]
+    fn test_run_lines_colors_every_line_separately() -> Result<(), Box<dyn Error>> {
+        let mut printr = Printr::builder().build().unwrap();
+        let mut output = vec![];
+        run_lines(
+            &mut printr,
+            Cursor::new("zealous\nbad\n"),
+            &mut output,
+            None,
+        )?;
+        assert_eq!(printr.config.color, None);
+        assert_eq!(
+            String::from_utf8(output)?,
+            "\u{1b}[32mzealous\u{1b}[0m\n\u{1b}[31mbad\u{1b}[0m\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_words_are_colored_by_their_own_score() {
+        let printr = run_printr(input("Zealous, but the badger is bad").words(true));
+        assert_eq!(
+            printr.to_string(),
+            "\u{1b}[32mZealous\u{1b}[0m, but the badger is \u{1b}[31mbad\u{1b}[0m\n"
+        );
+    }
+
+    #[test]
+    fn test_words_are_colored_by_the_tier_colors() {
+        let colors = ColorMap {
+            positive: Color::Blue,
+            negative: Color::Magenta,
+            ..ColorMap::default()
+        };
+        let printr = run_printr(input("great but bad").words(true).colors(colors));
+        assert_eq!(
+            printr.to_string(),
+            "\u{1b}[34mgreat\u{1b}[0m but \u{1b}[35mbad\u{1b}[0m\n"
+        );
+    }
+
+    #[test]
+    fn test_custom_analyzer_is_used() {
+        #[derive(Debug, PartialEq, Clone)]
+        struct Gloomy;
+
+        impl SentimentAnalyzer for Gloomy {
+            fn analyze(&self, text: &str) -> Sentiment {
+                let tokens = analyzer::tokenize(text)
+                    .into_iter()
+                    .map(|word| Token::new(word, -1.0))
+                    .collect();
+                Sentiment::from_tokens(tokens)
+            }
+        }
+
+        let printr = run_printr(input("zealous").analyzer(Gloomy));
+        assert_eq!(printr.to_string(), "\u{1b}[31mzealous\u{1b}[0m\n");
+    }
+
+    #[test]
+    fn test_explain_reports_the_decision() -> Result<(), Box<dyn Error>> {
+        let printr = run_printr(input("zealous day"));
+        let report: serde_json::Value = serde_json::from_str(&printr.explain())?;
+        assert_eq!(
+            report,
+            json!({
+                "text": "zealous day",
+                "positive": 2.0,
+                "negative": 0.0,
+                "polarity": "positive",
+                "words": [{ "word": "zealous", "weight": 2.0 }],
+                "color": "green",
+                "background": null,
+            })
+        );
+        Ok(())
+    }
 }
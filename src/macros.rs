@@ -0,0 +1,185 @@
+//! This module contains the helpers of the [`printr!`](../macro.printr.html) and
+//! [`eprintr!`](../macro.eprintr.html) macros, which are not meant to be used directly.
+use crate::{ColorMode, Format, Printr, PrintrBuilder};
+use std::io::{self, IsTerminal};
+
+/// Print the input to stdout, colored by its sentiment, like `println!` does.
+///
+/// The arguments are formatted like the ones of `println!`. They can be preceded by options
+/// in the form `name = value`, which correspond to the methods of
+/// [`PrintrBuilder`](struct.PrintrBuilder.html). The variants of [`Color`](enum.Color.html)
+/// can be used without their path in `color` and `background`, and the ones of
+/// [`Format`](enum.Format.html) in `format`, which takes one format or an array of them.
+///
+/// The output is only styled if stdout is a terminal, following
+/// [`ColorMode::Auto`](enum.ColorMode.html), unless `styling` is supplied.
+///
+/// ```
+/// use printr::printr;
+///
+/// let services = 3;
+/// printr!("Deployed {} services", services);
+/// printr!(color = Yellow, format = [Bold, Italic], "Deployed {} services", services);
+/// printr!(newline = false, plain = true, "no newline, no colors");
+/// ```
+///
+/// # Panics
+///
+/// This panics if the options contradict each other or if writing to stdout fails.
+#[macro_export]
+macro_rules! printr {
+    ($($arg:tt)+) => {
+        $crate::macros::print(
+            $crate::__printr_builder!([$crate::macros::builder(false)] $($arg)+),
+            false,
+        )
+    };
+}
+
+/// Print the input to stderr, colored by its sentiment, like `eprintln!` does.
+///
+/// This takes the same options as [`printr!`](macro.printr.html).
+///
+/// ```
+/// use printr::eprintr;
+///
+/// eprintr!(color = Red, "Could not deploy {} services", 2);
+/// ```
+///
+/// # Panics
+///
+/// This panics if the options contradict each other or if writing to stderr fails.
+#[macro_export]
+macro_rules! eprintr {
+    ($($arg:tt)+) => {
+        $crate::macros::print(
+            $crate::__printr_builder!([$crate::macros::builder(true)] $($arg)+),
+            true,
+        )
+    };
+}
+
+// applies the leading `name = value` options to the builder one by one and then sets the
+// formatted arguments as the input
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __printr_builder {
+    ([$($builder:tt)*] $name:ident = $value:expr, $($rest:tt)+) => {
+        $crate::__printr_builder!(
+            [$crate::__printr_option!($($builder)*, $name, $value)] $($rest)+
+        )
+    };
+    ([$($builder:tt)*] $($arg:tt)+) => {
+        $($builder)*.string(vec![format!($($arg)+)])
+    };
+}
+
+// applies a single option to the builder
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __printr_option {
+    ($builder:expr, color, $value:expr) => {{
+        #[allow(unused_imports)]
+        use $crate::Color::*;
+        $builder.color(Some($value))
+    }};
+    ($builder:expr, background, $value:expr) => {{
+        #[allow(unused_imports)]
+        use $crate::Color::*;
+        $builder.background(Some($crate::Background::Color($value)))
+    }};
+    ($builder:expr, format, $value:expr) => {{
+        #[allow(unused_imports)]
+        use $crate::Format::*;
+        $builder.format($crate::macros::Formats::formats($value))
+    }};
+    ($builder:expr, $name:ident, $value:expr) => {
+        $builder.$name($value)
+    };
+}
+
+/// The values accepted by the `format` option, that is, one format or several of them.
+pub trait Formats {
+    /// The formats of this value.
+    fn formats(self) -> Vec<Format>;
+}
+
+impl Formats for Format {
+    fn formats(self) -> Vec<Format> {
+        vec![self]
+    }
+}
+
+impl<const N: usize> Formats for [Format; N] {
+    fn formats(self) -> Vec<Format> {
+        self.to_vec()
+    }
+}
+
+impl Formats for Vec<Format> {
+    fn formats(self) -> Vec<Format> {
+        self
+    }
+}
+
+/// The builder the options of the macros are applied to, which styles the output if it is
+/// written to a terminal.
+pub fn builder(error: bool) -> PrintrBuilder {
+    let is_terminal = match error {
+        true => io::stderr().is_terminal(),
+        false => io::stdout().is_terminal(),
+    };
+    Printr::builder().styling(ColorMode::Auto.should_style(is_terminal))
+}
+
+/// Run the printr object of `builder` and print its output to stderr if `error` is `true`,
+/// otherwise to stdout.
+pub fn print(builder: PrintrBuilder, error: bool) {
+    let mut printr = builder
+        .build()
+        .unwrap_or_else(|err| panic!("could not create printr: {}", err));
+    printr.run_all_handles();
+    let (written, stream) = match error {
+        true => (printr.write_to(&mut io::stderr()), "stderr"),
+        false => (printr.write_to(&mut io::stdout()), "stdout"),
+    };
+    if let Err(err) = written {
+        panic!("failed printing to {}: {}", stream, err);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{tests::run_printr, Color, Printr};
+
+    #[test]
+    fn test_options_are_applied() {
+        let output_of = |builder| run_printr(builder).to_string();
+        let services = 3;
+        assert_eq!(
+            output_of(__printr_builder!([Printr::builder()] "Deployed {} services", services)),
+            "\u{1b}[34mDeployed 3 services\u{1b}[0m\n"
+        );
+        assert_eq!(
+            output_of(__printr_builder!(
+                [Printr::builder()]
+                color = Yellow,
+                format = [Bold, Italic],
+                newline = false,
+                "{}",
+                "bad"
+            )),
+            "\u{1b}[1;3;33mbad\u{1b}[0m"
+        );
+        let background = Color::Fixed(236);
+        assert_eq!(
+            output_of(__printr_builder!(
+                [Printr::builder()]
+                background = background,
+                format = Underline,
+                "bad"
+            )),
+            "\u{1b}[4;48;5;236;31mbad\u{1b}[0m\n"
+        );
+    }
+}
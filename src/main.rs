@@ -1,57 +1,260 @@
-use printr::{app::app, run, Color, Format, Printr};
+use clap::ErrorKind;
+use printr::{
+    analyzer::{Afinn, Thresholds},
+    app::app,
+    config::{self, Options},
+    language::Language,
+    lexicon::Lexicon,
+    run, run_lines,
+    theme::Theme,
+    Background, Color, ColorMap, ColorMode, Error, Printr, PrintrBuilder, STDIN,
+};
+use std::{
+    fmt::Display,
+    fs::File,
+    io::{self, BufRead, BufReader, Cursor, IsTerminal, Write},
+    process,
+    str::FromStr,
+};
 
 fn main() {
-    let matches = app().get_matches();
-    let string = matches
+    let matches = app()
+        .get_matches_safe()
+        .unwrap_or_else(|err| match err.kind {
+            ErrorKind::HelpDisplayed | ErrorKind::VersionDisplayed => err.exit(),
+            _ => {
+                eprintln!("{}", err.message);
+                // EX_USAGE, like every other invalid option
+                process::exit(64);
+            }
+        });
+    let options = Options::from_matches(&matches);
+    // the environment variables take precedence over the configuration file
+    let env = config::env_args()
+        .and_then(Options::parse)
+        .unwrap_or_else(|err| exit("Invalid environment variable", Error::InvalidOption(err)));
+    let mut options = options.with_defaults(env);
+    // the configuration file only supplies the options that were not supplied otherwise
+    if !options.is_present("no-config") {
+        if let Some(path) = config::path() {
+            let defaults = config::load(&path).unwrap_or_else(|err| {
+                exit(
+                    format!("Could not load config {}", path.display()),
+                    err.into(),
+                )
+            });
+            options = options.with_defaults(defaults);
+        }
+    }
+    let string = options
         .values_of("STRING")
         .map(|values| values.map(|s| s.to_string()).collect::<Vec<String>>());
-    let newline = matches.is_present("newline");
-    let spaces = matches.is_present("spaces");
-    let disable_interpretation = matches.is_present("disable_interpretation");
-    let enable_interpretation = matches.is_present("enable_interpretation");
+    let no_newline = options.is_present("newline");
+    let no_spaces = options.is_present("spaces");
+    let disable_interpretation = options.is_present("disable_interpretation");
+    let enable_interpretation = options.is_present("enable_interpretation");
     let interpretations = if disable_interpretation {
         false
     } else {
         enable_interpretation
     };
-    let maybe_file = matches.value_of("input-file").map(|f| f.to_string());
-    let plain = matches.is_present("plain");
-    let color = match matches.value_of("color") {
-        Some(c) => match c {
-            "blue" => Some(Color::Blue),
-            "red" => Some(Color::Red),
-            "yellow" => Some(Color::Yellow),
-            "green" => Some(Color::Green),
-            "cyan" => Some(Color::Cyan),
-            _ => None,
-        },
+    let maybe_file = match options.value_of("input-file") {
+        Some(f) => Some(f.to_string()),
+        // read the input from a pipe if nothing else was supplied
+        None if string.is_none() && !io::stdin().is_terminal() => Some(STDIN.to_string()),
         None => None,
     };
-    let error = matches.is_present("error");
-    let format = match matches.value_of("formatting") {
-        Some(f) => match f {
-            "bold" => Some(Format::Bold),
-            "dimmed" => Some(Format::Dimmed),
-            "underline" => Some(Format::Underline),
-            "strikethrough" => Some(Format::Strikethrough),
-            _ => None,
-        },
+    let plain = options.is_present("plain");
+    let color: Option<Color> = parse_value(&options, "color");
+    let error = options.is_present("error");
+    // the values have already been validated by clap
+    let color_mode: ColorMode = options
+        .value_of("color-mode")
+        .map_or(ColorMode::Auto, |mode| mode.parse().unwrap());
+    let styled = match error {
+        true => color_mode.should_style(io::stderr().is_terminal()),
+        false => color_mode.should_style(io::stdout().is_terminal()),
+    };
+    let words = options.is_present("words");
+    let gradient = options.is_present("gradient");
+    let background: Option<Background> = parse_value(&options, "background");
+    let theme = match options.value_of("theme") {
+        Some(name) => Theme::load(name)
+            .unwrap_or_else(|err| exit(format!("Could not load theme {}", name), err.into())),
+        None => Theme::default(),
+    };
+    let format = match options.values_of("formatting") {
+        // the values have already been validated by clap
+        Some(values) => values.map(|f| f.parse().unwrap()).collect(),
+        None => theme.formats.clone(),
+    };
+    let mut lexicon = Lexicon::new();
+    for path in options.values_of("lexicon").into_iter().flatten() {
+        let extra = Lexicon::from_file(path)
+            .unwrap_or_else(|err| exit(format!("Could not load lexicon {}", path), err.into()));
+        lexicon.extend(extra);
+    }
+    let language: Language = parse_value(&options, "lang").unwrap_or_default();
+    let analyzer = Afinn::with_lexicon(lexicon).language(language);
+    // the values have already been validated by clap
+    let threshold = |name| {
+        options
+            .value_of(name)
+            .map_or(0.0, |value: &str| value.parse().unwrap())
+    };
+    let thresholds = Thresholds {
+        neutral_band: threshold("neutral-band"),
+        neutral_ratio: threshold("neutral-ratio"),
+    };
+    let five_tiers = options.value_of("tiers") == Some("5")
+        || options.is_present("strong-threshold")
+        || options.is_present("strongly-positive-color")
+        || options.is_present("strongly-negative-color");
+    // the tiers supplied on the command line take precedence over the ones of the theme
+    let mut colors = match (five_tiers, options.is_present("tiers")) {
+        (true, _) => ColorMap::five_tiers(),
+        (false, true) => ColorMap::default(),
+        (false, false) => theme.colors.clone().unwrap_or_default(),
+    };
+    if let Some(threshold) = options.value_of("strong-threshold") {
+        colors.strong_threshold = threshold.parse().ok();
+    }
+    let tiers = [
+        ("strongly-positive-color", &mut colors.strongly_positive),
+        ("positive-color", &mut colors.positive),
+        ("neutral-color", &mut colors.neutral),
+        ("negative-color", &mut colors.negative),
+        ("strongly-negative-color", &mut colors.strongly_negative),
+    ];
+    let mut tiers_supplied = false;
+    for (name, color) in tiers {
+        if let Some(c) = parse_value(&options, name) {
+            *color = c;
+            tiers_supplied = true;
+        }
+    }
+    // a theme without colors leaves the output plain, unless colors are supplied
+    let colored = color.is_some()
+        || gradient
+        || background.is_some()
+        || options.is_present("tiers")
+        || tiers_supplied;
+    let plain = plain || (theme.colors.is_none() && !colored);
+    let mut explain: Option<Box<dyn Write>> = match options.value_of("explain-file") {
+        Some(path) => Some(Box::new(File::create(path).unwrap_or_else(|err| {
+            exit(format!("Could not create file {}", path), err.into())
+        }))),
+        None if options.is_present("explain") => Some(Box::new(io::stderr())),
         None => None,
     };
-    let mut printr = Printr::new(
-        interpretations,
-        newline,
-        plain,
-        spaces,
-        maybe_file,
-        color,
-        string,
-        format,
-    );
-    run(&mut printr);
+    let builder = Printr::builder()
+        .interpretations(interpretations)
+        .newline(!no_newline)
+        .plain(plain)
+        .spaces(!no_spaces)
+        .color(color)
+        .format(format)
+        // a theme without colors leaves the words plain as well
+        .words(words && !plain)
+        .thresholds(thresholds)
+        .colors(colors)
+        .gradient(gradient)
+        .background(background)
+        .styling(styled)
+        .prefix(theme.prefix, theme.icons)
+        .analyzer(analyzer);
+    if options.is_present("lines") {
+        let reader: Box<dyn BufRead> = match maybe_file {
+            Some(f) if f == STDIN => Box::new(io::stdin().lock()),
+            Some(f) => Box::new(BufReader::new(File::open(&f).unwrap_or_else(|err| {
+                let err = io::Error::new(err.kind(), format!("{}: {}", f, err));
+                exit(INPUT, Error::Io(err))
+            }))),
+            None => {
+                let separator = if no_spaces { "" } else { " " };
+                Box::new(Cursor::new(string.unwrap_or_default().join(separator)))
+            }
+        };
+        let mut printr = build(builder);
+        let writer: Box<dyn Write> = match error {
+            true => Box::new(io::stderr()),
+            false => Box::new(io::stdout()),
+        };
+        match run_lines(
+            &mut printr,
+            reader,
+            writer,
+            explain.as_mut().map(|w| w as &mut dyn Write),
+        ) {
+            Err(Error::Io(err)) if err.kind() == io::ErrorKind::BrokenPipe => {}
+            Err(err) => exit(INPUT, err),
+            Ok(()) => {}
+        }
+        return;
+    }
+    let builder = match (maybe_file, string) {
+        (Some(file), _) => builder.file(file),
+        (None, Some(string)) => builder.string(string),
+        (None, None) => builder,
+    };
+    let mut printr = build(builder);
+    run(&mut printr).unwrap_or_else(|err| exit(INPUT, err));
     // println!("{:#?}", &printr);
-    match error {
-        true => eprint!("{}", printr.get_output_string()),
-        false => print!("{}", printr.get_output_string()),
+    if let Some(explain) = explain.as_mut() {
+        if let Err(err) = writeln!(explain, "{}", printr.explain()) {
+            exit("Could not write the explanation", err.into());
+        }
+    }
+    let written = match error {
+        true => printr.write_to(&mut io::stderr()),
+        false => printr.write_to(&mut io::stdout()),
+    };
+    match written {
+        Err(err) if err.kind() == io::ErrorKind::BrokenPipe => {}
+        Err(err) => exit(INPUT, err.into()),
+        Ok(()) => {}
+    }
+}
+
+// the printr object of `builder`, exiting if it cannot be created
+fn build(builder: PrintrBuilder) -> Printr {
+    builder.build().unwrap_or_else(|err| exit(INPUT, err))
+}
+
+// the context of the errors that occur while printing the input
+const INPUT: &str = "Could not print the input";
+
+// report `err` after `context` and exit with the code of its class, following the
+// conventions of sysexits.h
+fn exit<C: Display>(context: C, err: Error) -> ! {
+    match &err {
+        // the context already says that an option is invalid
+        Error::InvalidOption(msg) => eprintln!("{}: {}", context, msg),
+        _ => eprintln!("{}: {}", context, err),
+    }
+    let code = match err {
+        // EX_USAGE, which includes unknown names such as the one of a theme
+        Error::InvalidOption(_) => 64,
+        Error::Io(ref err) if err.kind() == io::ErrorKind::InvalidInput => 64,
+        // EX_DATAERR, which includes files whose contents are invalid
+        Error::Encoding(_) => 65,
+        Error::Io(ref err) if err.kind() == io::ErrorKind::InvalidData => 65,
+        // EX_NOINPUT
+        Error::Io(ref err) if err.kind() == io::ErrorKind::NotFound => 66,
+        // EX_IOERR
+        Error::Io(_) => 74,
+    };
+    process::exit(code);
+}
+
+// the value supplied to the flag `name`, exiting if it is not a valid value
+fn parse_value<T: FromStr<Err = String>>(options: &Options, name: &str) -> Option<T> {
+    let value = options.value_of(name)?;
+    match value.parse() {
+        Ok(parsed) => Some(parsed),
+        Err(err) => exit(
+            format!("Invalid value for --{}", name),
+            Error::InvalidOption(err),
+        ),
     }
 }
@@ -0,0 +1,333 @@
+//! This module is responsible for themes, which bundle the colors that the sentiment tiers
+//! are mapped to, the formats of the output and a prefix into one reusable unit.
+//!
+//! Besides the built-in themes (refer to [`Theme::builtin`](struct.Theme.html#method.builtin)),
+//! themes can be defined in TOML files in the `themes` directory of the
+//! [configuration directory](../config/fn.config_dir.html), e.g. `~/.config/printr/themes/ci.toml`
+//! for a theme named `ci`:
+//!
+//! ```toml
+//! tiers = 5
+//! strong-threshold = 3
+//! formatting = ["bold"]
+//! prefix = "{icon} "
+//!
+//! [colors]
+//! strongly-positive = "#00ff00"
+//! positive = "green"
+//! neutral = "blue"
+//! negative = "yellow"
+//! strongly-negative = "rgb(255, 0, 0)"
+//!
+//! [icons]
+//! positive = "✔"
+//! neutral = "•"
+//! negative = "✖"
+//! ```
+//!
+//! All the keys are optional. A theme with `colored = false` does not color the output.
+use crate::{config::config_dir, Color, ColorMap, Format};
+use std::{
+    fs::read_to_string,
+    io::{self, ErrorKind},
+    path::Path,
+};
+use toml::{Table, Value};
+
+/// The names of the built-in themes.
+pub const BUILTIN_THEMES: &[&str] = &["default", "solarized", "high-contrast", "monochrome"];
+
+/// A set of options that determine how the output looks.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Theme {
+    /// The colors that the sentiment tiers are mapped to, or `None` if the output should
+    /// not be colored.
+    pub colors: Option<ColorMap>,
+    /// The formats applied to the output.
+    pub formats: Vec<Format>,
+    /// The icons of the sentiment tiers, which can be used in the `prefix`.
+    pub icons: Icons,
+    /// The text that is put before the output. Every `{icon}` in it is replaced by the icon
+    /// of the sentiment tier of the input and every `{tier}` by the name of the tier, e.g.
+    /// `strongly positive`.
+    pub prefix: String,
+}
+
+/// The icons of the sentiment tiers. The strongly positive and negative tiers share the
+/// icons of the positive and negative tiers.
+#[derive(Debug, PartialEq, Clone, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default, rename_all = "kebab-case")
+)]
+pub struct Icons {
+    /// The icon of positive input.
+    pub positive: String,
+    /// The icon of neutral input.
+    pub neutral: String,
+    /// The icon of negative input.
+    pub negative: String,
+}
+
+impl Icons {
+    fn new(positive: &str, neutral: &str, negative: &str) -> Self {
+        Self {
+            positive: positive.to_string(),
+            neutral: neutral.to_string(),
+            negative: negative.to_string(),
+        }
+    }
+}
+
+impl Default for Theme {
+    /// The theme used when none is supplied, which colors the output green, blue or red.
+    fn default() -> Self {
+        Self {
+            colors: Some(ColorMap::default()),
+            formats: vec![],
+            icons: Icons::default(),
+            prefix: String::new(),
+        }
+    }
+}
+
+impl Theme {
+    /// The built-in theme called `name`, if there is one. The built-in themes are:
+    ///
+    /// - `default`: green, blue and red.
+    /// - `solarized`: five tiers in the colors of the Solarized palette.
+    /// - `high-contrast`: bold and vivid colors, with icons before the output.
+    /// - `monochrome`: no colors, only a `[+]`, `[=]` or `[-]` before the output.
+    pub fn builtin(name: &str) -> Option<Self> {
+        let theme = match name {
+            "default" => Self::default(),
+            "solarized" => Self {
+                colors: Some(ColorMap {
+                    strongly_positive: Color::Rgb(0x85, 0x99, 0x00),
+                    positive: Color::Rgb(0x2a, 0xa1, 0x98),
+                    neutral: Color::Rgb(0x83, 0x94, 0x96),
+                    negative: Color::Rgb(0xb5, 0x89, 0x00),
+                    strongly_negative: Color::Rgb(0xdc, 0x32, 0x2f),
+                    strong_threshold: Some(ColorMap::STRONG_THRESHOLD),
+                }),
+                ..Self::default()
+            },
+            "high-contrast" => Self {
+                colors: Some(ColorMap {
+                    strongly_positive: Color::Fixed(46),
+                    positive: Color::Fixed(46),
+                    neutral: Color::Fixed(231),
+                    negative: Color::Fixed(196),
+                    strongly_negative: Color::Fixed(196),
+                    strong_threshold: None,
+                }),
+                formats: vec![Format::Bold],
+                icons: Icons::new("✔", "•", "✖"),
+                prefix: "{icon} ".to_string(),
+            },
+            "monochrome" => Self {
+                colors: None,
+                icons: Icons::new("[+]", "[=]", "[-]"),
+                prefix: "{icon} ".to_string(),
+                ..Self::default()
+            },
+            _ => return None,
+        };
+        Some(theme)
+    }
+
+    /// Load the theme called `name`, which is either a built-in theme or a user-defined one
+    /// in the `themes` directory of the configuration directory. User-defined themes take
+    /// precedence over the built-in ones. An unknown name is reported as an
+    /// [`InvalidInput`](https://doc.rust-lang.org/std/io/enum.ErrorKind.html) error.
+    pub fn load(name: &str) -> io::Result<Self> {
+        let path = config_dir().map(|dir| dir.join("themes").join(format!("{}.toml", name)));
+        match path {
+            Some(path) if path.is_file() => Self::from_file(path),
+            _ => Self::builtin(name).ok_or_else(|| {
+                let msg = format!(
+                    "no such theme, the built-in themes are {}",
+                    BUILTIN_THEMES.join(", ")
+                );
+                io::Error::new(ErrorKind::InvalidInput, msg)
+            }),
+        }
+    }
+
+    /// Load a theme from a TOML file, as described in the [module documentation](index.html).
+    pub fn from_file<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let contents = read_to_string(path)?;
+        Self::parse(&contents).map_err(|err| io::Error::new(ErrorKind::InvalidData, err))
+    }
+
+    /// Parse a theme in the TOML format, as described in the [module
+    /// documentation](index.html).
+    pub fn parse(contents: &str) -> Result<Self, String> {
+        let table: Table = contents
+            .parse()
+            .map_err(|err: toml::de::Error| err.message().to_string())?;
+        check_keys(
+            &table,
+            &[
+                "tiers",
+                "strong-threshold",
+                "colors",
+                "colored",
+                "formatting",
+                "icons",
+                "prefix",
+            ],
+        )?;
+        let mut theme = Self::default();
+        let mut colors = match table.get("tiers") {
+            Some(Value::Integer(5)) => ColorMap::five_tiers(),
+            Some(Value::Integer(3)) | None => ColorMap::default(),
+            Some(_) => return Err("expected 3 or 5 for \"tiers\"".to_string()),
+        };
+        match table.get("strong-threshold") {
+            Some(Value::Integer(threshold)) => colors.strong_threshold = Some(*threshold as f32),
+            Some(Value::Float(threshold)) => colors.strong_threshold = Some(*threshold as f32),
+            Some(_) => return Err("expected a number for \"strong-threshold\"".to_string()),
+            None => {}
+        }
+        if let Some(table) = table_of(&table, "colors")? {
+            check_keys(
+                table,
+                &[
+                    "strongly-positive",
+                    "positive",
+                    "neutral",
+                    "negative",
+                    "strongly-negative",
+                ],
+            )?;
+            let tiers = [
+                ("strongly-positive", &mut colors.strongly_positive),
+                ("positive", &mut colors.positive),
+                ("neutral", &mut colors.neutral),
+                ("negative", &mut colors.negative),
+                ("strongly-negative", &mut colors.strongly_negative),
+            ];
+            for (key, color) in tiers {
+                if let Some(value) = string(table, key)? {
+                    *color = value.parse()?;
+                }
+            }
+        }
+        theme.colors = match table.get("colored") {
+            Some(Value::Boolean(false)) => None,
+            Some(Value::Boolean(true)) | None => Some(colors),
+            Some(_) => return Err("expected true or false for \"colored\"".to_string()),
+        };
+        if let Some(value) = table.get("formatting") {
+            let formats = value
+                .as_array()
+                .ok_or("expected an array for \"formatting\"")?;
+            for format in formats {
+                let format = format
+                    .as_str()
+                    .ok_or("expected an array of strings for \"formatting\"")?;
+                theme.formats.push(format.parse()?);
+            }
+        }
+        if let Some(table) = table_of(&table, "icons")? {
+            check_keys(table, &["positive", "neutral", "negative"])?;
+            let icons = [
+                ("positive", &mut theme.icons.positive),
+                ("neutral", &mut theme.icons.neutral),
+                ("negative", &mut theme.icons.negative),
+            ];
+            for (key, icon) in icons {
+                if let Some(value) = string(table, key)? {
+                    *icon = value.to_string();
+                }
+            }
+        }
+        if let Some(prefix) = string(&table, "prefix")? {
+            theme.prefix = prefix.to_string();
+        }
+        Ok(theme)
+    }
+}
+
+// check that every key of `table` is one of `known`
+fn check_keys(table: &Table, known: &[&str]) -> Result<(), String> {
+    match table.keys().find(|key| !known.contains(&key.as_str())) {
+        Some(unknown) => Err(format!("unknown key {:?}", unknown)),
+        None => Ok(()),
+    }
+}
+
+// the string value of `key` in `table`
+fn string<'a>(table: &'a Table, key: &str) -> Result<Option<&'a str>, String> {
+    match table.get(key) {
+        Some(value) => value
+            .as_str()
+            .map(Some)
+            .ok_or_else(|| format!("expected a string for {:?}", key)),
+        None => Ok(None),
+    }
+}
+
+// the table value of `key` in `table`
+fn table_of<'a>(table: &'a Table, key: &str) -> Result<Option<&'a Table>, String> {
+    match table.get(key) {
+        Some(value) => value
+            .as_table()
+            .map(Some)
+            .ok_or_else(|| format!("expected a table for {:?}", key)),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_themes() {
+        for name in BUILTIN_THEMES {
+            assert!(Theme::builtin(name).is_some(), "{} is missing", name);
+        }
+        assert_eq!(Theme::builtin("default"), Some(Theme::default()));
+        assert_eq!(Theme::builtin("neon"), None);
+    }
+
+    #[test]
+    fn test_parse() {
+        let theme = Theme::parse(
+            r##"
+            tiers = 5
+            formatting = ["bold", "italic"]
+            prefix = "{icon} "
+
+            [colors]
+            positive = "#00ff00"
+
+            [icons]
+            negative = "✖"
+            "##,
+        );
+        assert_eq!(
+            theme,
+            Ok(Theme {
+                colors: Some(ColorMap {
+                    positive: Color::Rgb(0, 255, 0),
+                    ..ColorMap::five_tiers()
+                }),
+                formats: vec![Format::Bold, Format::Italic],
+                icons: Icons::new("", "", "✖"),
+                prefix: "{icon} ".to_string(),
+            })
+        );
+        assert_eq!(
+            Theme::parse("colored = false").map(|theme| theme.colors),
+            Ok(None)
+        );
+        assert_eq!(
+            Theme::parse("[colors]\npositve = \"red\""),
+            Err("unknown key \"positve\"".to_string())
+        );
+    }
+}
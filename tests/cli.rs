@@ -0,0 +1,236 @@
+use assert_cmd::Command;
+use printr::{app::all_args_and_flags, config::PRINTR_CONFIG};
+use std::{error::Error, io::Write};
+use tempfile::NamedTempFile;
+
+// the output of these tests is kept plain, since the coloring is already tested in the unit
+// tests of the library
+
+// the printr binary, unaffected by the environment variables and the configuration file of
+// whoever runs the tests
+fn printr() -> Result<Command, Box<dyn Error>> {
+    let mut command = Command::cargo_bin("printr")?;
+    for env in all_args_and_flags().into_iter().filter_map(|arg| arg.env) {
+        command.env_remove(env);
+    }
+    command
+        .env_remove(PRINTR_CONFIG)
+        .env("XDG_CONFIG_HOME", env!("CARGO_TARGET_TMPDIR"));
+    Ok(command)
+}
+
+#[test]
+fn test_input_is_read_from_stdin_when_piped() -> Result<(), Box<dyn Error>> {
+    printr()?
+        .arg("--plain")
+        .write_stdin("Hello from a pipe\n")
+        .assert()
+        .success()
+        .stdout("Hello from a pipe\n");
+    Ok(())
+}
+
+#[test]
+fn test_input_file_dash_reads_from_stdin() -> Result<(), Box<dyn Error>> {
+    printr()?
+        .args(["--plain", "--newline", "--input-file", "-"])
+        .write_stdin("first\nsecond\n")
+        .assert()
+        .success()
+        .stdout("first\nsecond");
+    Ok(())
+}
+
+#[test]
+fn test_string_takes_precedence_over_stdin() -> Result<(), Box<dyn Error>> {
+    printr()?
+        .args(["--plain", "Hello"])
+        .write_stdin("ignored")
+        .assert()
+        .success()
+        .stdout("Hello\n");
+    Ok(())
+}
+
+#[test]
+fn test_lines_are_printed_one_by_one() -> Result<(), Box<dyn Error>> {
+    printr()?
+        .args(["--plain", "--lines", "-e"])
+        .write_stdin("one\\ttab\nstop\\c here\nnever printed\n")
+        .assert()
+        .success()
+        .stdout("one\ttab\nstop");
+    Ok(())
+}
+
+#[test]
+fn test_explain_does_not_change_the_output() -> Result<(), Box<dyn Error>> {
+    printr()?
+        .args(["--plain", "--explain", "Bad"])
+        .assert()
+        .success()
+        .stdout("Bad\n")
+        .stderr(predicates::str::contains(r#""polarity":"negative""#));
+    Ok(())
+}
+
+#[test]
+fn test_output_is_only_styled_for_a_terminal() -> Result<(), Box<dyn Error>> {
+    printr()?
+        .arg("bad")
+        .env_remove("CLICOLOR_FORCE")
+        .assert()
+        .success()
+        .stdout("bad\n");
+    printr()?
+        .args(["--color-mode", "always", "bad"])
+        .env("NO_COLOR", "1")
+        .assert()
+        .success()
+        .stdout("\u{1b}[31mbad\u{1b}[0m\n");
+    Ok(())
+}
+
+#[test]
+fn test_flags_override_the_config_file() -> Result<(), Box<dyn Error>> {
+    let mut config = NamedTempFile::new()?;
+    write!(
+        config,
+        "color-mode = \"always\"\ncolor = \"red\"\nnewline = true"
+    )?;
+    printr()?
+        .args(["--color", "blue", "Hello"])
+        .env(PRINTR_CONFIG, config.path())
+        .assert()
+        .success()
+        .stdout("\u{1b}[34mHello\u{1b}[0m");
+    printr()?
+        .args(["--no-config", "Hello"])
+        .env(PRINTR_CONFIG, config.path())
+        .assert()
+        .success()
+        .stdout("Hello\n");
+    Ok(())
+}
+
+#[test]
+fn test_environment_overrides_config_but_not_arguments() -> Result<(), Box<dyn Error>> {
+    let mut config = NamedTempFile::new()?;
+    writeln!(config, "color = \"red\"")?;
+    printr()?
+        .env(PRINTR_CONFIG, config.path())
+        .env("PRINTR_COLOR", "green")
+        .env("PRINTR_COLOR_MODE", "always")
+        .arg("hello")
+        .assert()
+        .success()
+        .stdout("\u{1b}[32mhello\u{1b}[0m\n");
+    printr()?
+        .env(PRINTR_CONFIG, config.path())
+        .env("PRINTR_COLOR", "green")
+        .env("PRINTR_COLOR_MODE", "always")
+        .args(["--color", "blue", "hello"])
+        .assert()
+        .success()
+        .stdout("\u{1b}[34mhello\u{1b}[0m\n");
+    Ok(())
+}
+
+#[test]
+fn test_defaults_that_conflict_with_arguments_are_ignored() -> Result<(), Box<dyn Error>> {
+    printr()?
+        .env("PRINTR_COLOR", "red")
+        .args(["--plain", "hi"])
+        .assert()
+        .success()
+        .stdout("hi\n");
+    printr()?
+        .env("PRINTR_INPUT_FILE", "/nonexistent/printr")
+        .args(["--plain", "hi"])
+        .assert()
+        .success()
+        .stdout("hi\n");
+    printr()?
+        .env("PRINTR_WORDS", "1")
+        .args(["--color-mode", "always", "--color", "red", "hi"])
+        .assert()
+        .success()
+        .stdout("\u{1b}[31mhi\u{1b}[0m\n");
+    let mut config = NamedTempFile::new()?;
+    write!(config, "color = \"red\"\nnewline = true")?;
+    printr()?
+        .env(PRINTR_CONFIG, config.path())
+        .args(["--plain", "hi"])
+        .assert()
+        .success()
+        .stdout("hi");
+    Ok(())
+}
+
+#[test]
+fn test_switches_of_the_defaults_can_be_turned_off() -> Result<(), Box<dyn Error>> {
+    let mut config = NamedTempFile::new()?;
+    write!(config, "newline = true\nplain = true")?;
+    printr()?
+        .env(PRINTR_CONFIG, config.path())
+        .args(["--no-newline", "hi"])
+        .assert()
+        .success()
+        .stdout("hi\n");
+    printr()?
+        .env(PRINTR_CONFIG, config.path())
+        .env("PRINTR_NEWLINE", "off")
+        .arg("hi")
+        .assert()
+        .success()
+        .stdout("hi\n");
+    Ok(())
+}
+
+#[test]
+fn test_errors_exit_with_the_code_of_their_class() -> Result<(), Box<dyn Error>> {
+    printr()?
+        .args(["--input-file", "/nonexistent/printr"])
+        .assert()
+        .code(66)
+        .stderr(predicates::str::contains("/nonexistent/printr"));
+    let mut file = NamedTempFile::new()?;
+    file.write_all(b"zealous \xff")?;
+    printr()?
+        .arg("--input-file")
+        .arg(file.path())
+        .assert()
+        .code(65);
+    printr()?
+        .args(["--color", "mauve", "hi"])
+        .assert()
+        .code(64)
+        .stderr(predicates::str::starts_with(
+            "Invalid value for --color: unknown color",
+        ));
+    printr()?
+        .env("PRINTR_WORDS", "maybe")
+        .arg("hi")
+        .assert()
+        .code(64);
+    printr()?
+        .args(["--color-mode", "bogus", "hi"])
+        .assert()
+        .code(64);
+    printr()?
+        .args(["--neutral-band", "-1", "hi"])
+        .assert()
+        .code(64);
+    printr()?
+        .args(["--theme", "bogus", "hi"])
+        .assert()
+        .code(64)
+        .stderr(predicates::str::contains("no such theme"));
+    printr()?.arg("--help").assert().success();
+    printr()?
+        .args(["--lexicon", "/nonexistent/printr.tsv", "hi"])
+        .assert()
+        .code(66)
+        .stderr(predicates::str::contains("/nonexistent/printr.tsv"));
+    Ok(())
+}